@@ -0,0 +1,49 @@
+use crate::type_expression::TypeExpression;
+
+
+/// Tells whether a template with the given name and number of arguments is `boost::msm::front::ActionSequence_`.
+pub(crate) fn isActionSequence(name: &str, argumentCount: usize) -> bool
+{
+    argumentCount == 1 && (name == "ActionSequence_" || name.ends_with("::ActionSequence_"))
+}
+
+/// Lists actions in the order of execution, with nested action sequences flattened.
+pub(crate) fn flattenActions(action: &TypeExpression) -> Vec<TypeExpression>
+{
+    match action {
+        TypeExpression::Template{name, arguments} if isActionSequence(name, arguments.len()) => match &arguments[0] {
+            TypeExpression::Template{name, arguments} if isSequence(name) => arguments.iter().flat_map(flattenActions).collect(),
+            _ => vec![action.clone()]
+        },
+        _ => vec![action.clone()]
+    }
+}
+
+/// Tells whether a template is a type sequence from `boost::mpl`, like `vector` or `list3`.
+pub(crate) fn isSequence(name: &str) -> bool
+{
+    let unqualifiedName = match name.rfind("::") {
+        Some(index) => &name[index + 2..],
+        None => name
+    };
+    unqualifiedName.starts_with("vector") || unqualifiedName.starts_with("list")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ActionListLayout
+{
+    Inline,
+    Lines
+}
+
+impl ActionListLayout
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "inline" => Ok(Self::Inline),
+            "lines" => Ok(Self::Lines),
+            _ => Err(format!("Unknown action list layout: {}, expected one of: inline, lines.", name))
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::cli::{Options, isStdin};
+use crate::guard_expression::GuardExpression;
+use crate::input_files::{collectInputFiles, readInputFile};
+use crate::machine::{Machine, parseMachines};
+use crate::row::Row;
+use crate::transition_label::shouldBeShown;
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+
+const STDIN_DISPLAY_NAME: &str = "<stdin>";
+
+pub(crate) struct Finding
+{
+    pub(crate) severity: Severity,
+    pub(crate) line: usize,
+    pub(crate) message: String,
+    pub(crate) notes: Vec<FindingNote>
+}
+
+pub(crate) struct FindingNote
+{
+    pub(crate) line: usize,
+    pub(crate) message: String
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Severity
+{
+    Warning,
+    Error
+}
+
+impl Severity
+{
+    pub(crate) fn name(self) -> &'static str
+    {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error"
+        }
+    }
+}
+
+impl Finding
+{
+    /// Formats the finding and its notes in the `file:line: severity: message` form understood by editors.
+    pub(crate) fn render(&self, path: &str) -> String
+    {
+        let mut text = format!("{}:{}: {}: {}\n", path, self.line, self.severity.name(), self.message);
+        for note in &self.notes {
+            text += &format!("{}:{}: note: {}\n", path, note.line, note.message);
+        }
+        text
+    }
+}
+
+/// Rendered findings of every machine found in the input files, with their counts.
+pub(crate) struct Report
+{
+    pub(crate) text: String,
+    pub(crate) machineCount: usize,
+    pub(crate) warningCount: usize,
+    pub(crate) errorCount: usize
+}
+
+/// Analyzes every machine found in the input files and returns a report listing the findings.
+pub(crate) fn analyzeInputFiles(options: &Options) -> Result<Report,String>
+{
+    checkInputFiles(options, analyzeMachine)
+}
+
+/// Runs `check` on every machine found in the input files, giving it also the text of the file the machine is in, and
+/// ends the report with a summary.
+pub(crate) fn checkInputFiles(options: &Options, mut check: impl FnMut(&Machine, &str) -> Vec<Finding>) -> Result<Report,String>
+{
+    let inputFiles = collectInputFiles(&options.inputPaths, &options.includePatterns, &options.excludePatterns)?;
+    let mut report = Report{text: String::new(), machineCount: 0, warningCount: 0, errorCount: 0};
+    for inputFile in inputFiles {
+        let displayedPath = displayPath(&inputFile.path);
+        let fileContent = readInputFile(&inputFile.path)?;
+        let machines = match parseMachines(&fileContent, options.nameDisplay) {
+            Ok(machines) => machines,
+            Err(e) => return Err(format!("{}: {}", displayedPath, e))
+        };
+        if machines.is_empty() && !inputFile.wasFoundInDirectory {
+            return Err(format!("{}: Transition table was not found.", displayedPath));
+        }
+
+        for machine in &machines {
+            report.machineCount += 1;
+            for finding in check(machine, &fileContent) {
+                match finding.severity {
+                    Severity::Warning => report.warningCount += 1,
+                    Severity::Error => report.errorCount += 1
+                }
+                report.text += &finding.render(&displayedPath);
+            }
+        }
+    }
+
+    if report.machineCount == 0 {
+        return Err("Transition table was not found.".into());
+    }
+    report.text += &format!("Summary: {} error(s) and {} warning(s) in {} machine(s).\n",
+        report.errorCount, report.warningCount, report.machineCount);
+    Ok(report)
+}
+
+pub(crate) fn analyzeMachine(machine: &Machine, fileContent: &str) -> Vec<Finding>
+{
+    let terminalStates = findTerminalStates(fileContent);
+    let mut findings = findUnreachableStates(machine);
+    findings.extend(findDeadEndStates(machine, &terminalStates));
+    findings.extend(findConflictingTransitions(machine));
+    findings.extend(findAnonymousTransitionCycles(machine));
+    findings.sort_by_key(|finding| finding.line);
+    findings
+}
+
+fn findUnreachableStates(machine: &Machine) -> Vec<Finding>
+{
+    let reachableStates = collectReachableStates(machine);
+    listStates(machine).into_iter()
+        .filter(|state| !reachableStates.contains(state))
+        .map(|state| {
+            let message = format!("state {} in {} is unreachable from the initial state", state, machine.name);
+            makeFinding(machine, state, message, |row| row.start == state || row.target == state)
+        })
+        .collect()
+}
+
+fn findDeadEndStates(machine: &Machine, terminalStates: &HashSet<String>) -> Vec<Finding>
+{
+    listStates(machine).into_iter()
+        .filter(|state| !machine.rows.iter().any(|row| row.start == *state))
+        .filter(|state| !terminalStates.contains(unqualifiedName(state)))
+        .map(|state| {
+            let message = format!("state {} in {} has no outgoing transitions", state, machine.name);
+            makeFinding(machine, state, message, |row| row.target == state)
+        })
+        .collect()
+}
+
+/// Finds pairs of rows with the same start state and event. As boost::msm tries rows from the bottom of the table, the
+/// findings point at the later row and the notes at the earlier one.
+fn findConflictingTransitions(machine: &Machine) -> Vec<Finding>
+{
+    let mut findings = vec![];
+    for (index, laterRow) in machine.rows.iter().enumerate() {
+        for earlierRow in machine.rows[..index].iter().filter(|row| row.start == laterRow.start && row.event == laterRow.event) {
+            let (message, note) = match (&earlierRow.guard, &laterRow.guard) {
+                (earlierGuard, laterGuard) if earlierGuard == laterGuard || laterGuard.is_none() => (
+                    format!("conflicting transitions from {} on {} in {}, boost::msm tries this row first",
+                        laterRow.start, laterRow.event, machine.name),
+                    "this row is never taken"),
+                // The earlier row without a guard is a fallback, taken only when the guard of the later one does not hold.
+                (None, Some(_)) => continue,
+                (Some(earlierGuard), Some(laterGuard))
+                    if GuardExpression::new(earlierGuard).excludes(&GuardExpression::new(laterGuard)) => continue,
+                _ => (
+                    format!("guarded transitions from {} on {} in {} may overlap, boost::msm tries this row first",
+                        laterRow.start, laterRow.event, machine.name),
+                    "this row is taken only when the guard of the other one does not hold")
+            };
+            let notes = vec![FindingNote{line: earlierRow.line, message: note.into()}];
+            findings.push(Finding{severity: Severity::Warning, line: laterRow.line, message, notes});
+        }
+    }
+    findings
+}
+
+/// Finds strongly connected components made only of anonymous transitions without guards. As boost::msm takes such
+/// transitions as soon as their start state is entered, it never leaves these components.
+fn findAnonymousTransitionCycles(machine: &Machine) -> Vec<Finding>
+{
+    let cycleRows: Vec<&Row> = machine.rows.iter()
+        .filter(|row| !shouldBeShown(&row.event) && row.guard.is_none() && shouldBeShown(&row.target))
+        .collect();
+    let states = listStates(machine);
+    let stateIndexes: HashMap<&str, usize> = states.iter().enumerate().map(|(index, &state)| (state, index)).collect();
+    let mut successors = vec![vec![]; states.len()];
+    for row in &cycleRows {
+        successors[stateIndexes[row.start.as_str()]].push(stateIndexes[row.target.as_str()]);
+    }
+
+    let mut components = findStronglyConnectedComponents(&successors);
+    components.iter_mut().for_each(|component| component.sort());
+    components.sort();
+    let mut findings = vec![];
+    for component in components {
+        let component: Vec<&str> = component.into_iter().map(|index| states[index]).collect();
+        let mut rows = cycleRows.iter().filter(|row| component.contains(&row.start.as_str()) && component.contains(&row.target.as_str()));
+        // A component of a single state without a row looping on it is not a cycle.
+        if let Some(firstRow) = rows.next() {
+            let message = format!("anonymous transitions without guards between {} in {} form a cycle, boost::msm takes them forever",
+                component.join(", "), machine.name);
+            let notes = rows.map(|row| FindingNote{line: row.line, message: "this row is also a part of the cycle".into()}).collect();
+            findings.push(Finding{severity: Severity::Error, line: firstRow.line, message, notes});
+        }
+    }
+    findings
+}
+
+/// Finds strongly connected components of a graph given by successors of its nodes, with Tarjan's algorithm.
+fn findStronglyConnectedComponents(successors: &[Vec<usize>]) -> Vec<Vec<usize>>
+{
+    struct Search<'a>
+    {
+        successors: &'a [Vec<usize>],
+        nextIndex: usize,
+        indexes: Vec<Option<usize>>,
+        lowLinks: Vec<usize>,
+        stack: Vec<usize>,
+        isOnStack: Vec<bool>,
+        components: Vec<Vec<usize>>
+    }
+
+    impl Search<'_>
+    {
+        fn visit(&mut self, node: usize)
+        {
+            let index = self.nextIndex;
+            self.nextIndex += 1;
+            self.indexes[node] = Some(index);
+            self.lowLinks[node] = index;
+            self.stack.push(node);
+            self.isOnStack[node] = true;
+            for &successor in &self.successors[node] {
+                match self.indexes[successor] {
+                    None => {
+                        self.visit(successor);
+                        self.lowLinks[node] = self.lowLinks[node].min(self.lowLinks[successor]);
+                    },
+                    Some(successorIndex) if self.isOnStack[successor] =>
+                        self.lowLinks[node] = self.lowLinks[node].min(successorIndex),
+                    Some(_) => ()
+                }
+            }
+            if self.lowLinks[node] == index {
+                let mut component = vec![];
+                while let Some(member) = self.stack.pop() {
+                    self.isOnStack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let nodeCount = successors.len();
+    let mut search = Search{
+        successors,
+        nextIndex: 0,
+        indexes: vec![None; nodeCount],
+        lowLinks: vec![0; nodeCount],
+        stack: vec![],
+        isOnStack: vec![false; nodeCount],
+        components: vec![]
+    };
+    for node in 0..nodeCount {
+        if search.indexes[node].is_none() {
+            search.visit(node);
+        }
+    }
+    search.components
+}
+
+/// Creates a finding located at the first row matching `isMentioned`, with notes pointing at the other ones.
+pub(crate) fn makeFinding(machine: &Machine, state: &str, message: String, isMentioned: impl Fn(&Row) -> bool) -> Finding
+{
+    let mut lines = machine.rows.iter().filter(|row| isMentioned(row)).map(|row| row.line);
+    let line = lines.next().unwrap_or_default();
+    let notes = lines.map(|line| FindingNote{line, message: format!("{} is also mentioned here", state)}).collect();
+    Finding{severity: Severity::Warning, line, message, notes}
+}
+
+/// Lists states in the order of their first appearance in the transition table. The `none` target of an internal
+/// transition is not a state.
+pub(crate) fn listStates(machine: &Machine) -> Vec<&str>
+{
+    let mut states: Vec<&str> = vec![];
+    for row in &machine.rows {
+        for state in [&row.start, &row.target] {
+            if shouldBeShown(state) && !states.contains(&state.as_str()) {
+                states.push(state);
+            }
+        }
+    }
+    states
+}
+
+fn collectReachableStates(machine: &Machine) -> HashSet<&str>
+{
+    let mut reachableStates: HashSet<&str> = machine.initialStates.iter().map(|state| state.as_str()).collect();
+    let mut queue: VecDeque<&str> = reachableStates.iter().copied().collect();
+    while let Some(state) = queue.pop_front() {
+        // An internal transition stays in its start state, which is already reachable.
+        for row in machine.rows.iter().filter(|row| row.start == state && shouldBeShown(&row.target)) {
+            if reachableStates.insert(&row.target) {
+                queue.push_back(&row.target);
+            }
+        }
+    }
+    reachableStates
+}
+
+/// Finds names of states deriving from `terminate_state` or `exit_pseudo_state`, which are expected to have no
+/// outgoing transitions.
+pub(crate) fn findTerminalStates(text: &str) -> HashSet<String>
+{
+    let terminalStateRegex =
+        Regex::new(r"\b(?:struct|class)\s+(\w+)\s*(?:final\s*)?:[^{;]*\b(?:terminate_state|exit_pseudo_state)\b").unwrap();
+    terminalStateRegex.captures_iter(text).map(|captures| captures[1].to_string()).collect()
+}
+
+pub(crate) fn unqualifiedName(name: &str) -> &str
+{
+    let name = match name.find('<') {
+        Some(index) => &name[..index],
+        None => name
+    };
+    match name.rfind("::") {
+        Some(index) => &name[index + 2..],
+        None => name
+    }
+}
+
+pub(crate) fn displayPath(path: &Path) -> String
+{
+    match isStdin(path) {
+        true => STDIN_DISPLAY_NAME.into(),
+        false => path.display().to_string()
+    }
+}
@@ -0,0 +1,28 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+
+const NOTE_ANNOTATION: &str = "@msm-note";
+
+/// Finds display names of states given with `// @msm-label "Name"` in a comment above the struct or class defining
+/// them, possibly followed by other comments, keyed by the name of the struct.
+pub(crate) fn collectStateLabels(text: &str) -> HashMap<String, String>
+{
+    let labelRegex = Regex::new(
+        r#"(?m)^[ \t]*//[ \t]*@msm-label[ \t]+"([^"\n]*)"[^\n]*\n(?:[ \t]*//[^\n]*\n)*[ \t]*(?:struct|class)[ \t]+(\w+)"#).unwrap();
+    labelRegex.captures_iter(text).map(|captures| (captures[2].to_string(), captures[1].to_string())).collect()
+}
+
+/// Returns the text of `// @msm-note text` comments of a row, each in its own line, with optional quotes removed.
+pub(crate) fn findRowNote(comments: &[String]) -> Option<String>
+{
+    let notes: Vec<&str> = comments.iter()
+        .filter_map(|comment| comment.strip_prefix(NOTE_ANNOTATION))
+        .map(|note| note.trim())
+        .map(|note| note.strip_prefix('"').and_then(|note| note.strip_suffix('"')).unwrap_or(note))
+        .collect();
+    match notes.is_empty() {
+        true => None,
+        false => Some(notes.join("\n"))
+    }
+}
@@ -0,0 +1,77 @@
+use crate::cli::Options;
+use crate::diagram::buildDiagram;
+use crate::input_files::{collectInputFiles, readInputFile};
+use crate::machine::parseMachines;
+use crate::renderer::renderDiagram;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+
+pub(crate) fn generateDiagramsInBatch(options: &Options, outputDirectory: &Path) -> Result<(),String>
+{
+    let diagramOptions = options.makeDiagramOptions()?;
+    let inputFiles = collectInputFiles(&options.inputPaths, &options.includePatterns, &options.excludePatterns)?;
+    if let Err(e) = std::fs::create_dir_all(outputDirectory) {
+        return Err(format!("Failed to create output directory: {:?}, error: {}", outputDirectory, e));
+    }
+
+    let mut usedFileNames = HashSet::new();
+    let mut generatedCount = 0;
+    let mut skippedCount = 0;
+    let mut failures = vec![];
+    for inputFile in inputFiles {
+        let fileContent = match readInputFile(&inputFile.path) {
+            Ok(content) => content,
+            Err(e) => {
+                failures.push(format!("{}: {}", inputFile.path.display(), e));
+                continue;
+            }
+        };
+        let machines = match parseMachines(&fileContent, options.nameDisplay) {
+            Ok(machines) => machines,
+            Err(e) => {
+                failures.push(format!("{}: {}", inputFile.path.display(), e));
+                continue;
+            }
+        };
+        if machines.is_empty() {
+            match inputFile.wasFoundInDirectory {
+                true => skippedCount += 1,
+                false => failures.push(format!("{}: Transition table was not found.", inputFile.path.display()))
+            }
+            continue;
+        }
+        for machine in machines {
+            let outputPath = outputDirectory.join(makeUniqueFileName(&machine.name, options.format.fileExtension(), &mut usedFileNames));
+            if let Err(e) = std::fs::write(&outputPath, renderDiagram(&buildDiagram(&machine, &diagramOptions), &diagramOptions, options.format) + "\n") {
+                failures.push(format!("{}: failed to write {}: {}", inputFile.path.display(), outputPath.display(), e));
+                continue;
+            }
+            println!("Generated {} from {}", outputPath.display(), inputFile.path.display());
+            generatedCount += 1;
+        }
+    }
+
+    for failure in &failures {
+        println!("Failed {}", failure);
+    }
+    println!("Summary: {} diagram(s) generated, {} file(s) failed, {} file(s) without a transition table skipped.",
+        generatedCount, failures.len(), skippedCount);
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(format!("Failed to process {} file(s).", failures.len()))
+    }
+}
+
+fn makeUniqueFileName(machineName: &str, extension: &str, usedFileNames: &mut HashSet<PathBuf>) -> PathBuf
+{
+    let mut fileName = PathBuf::from(format!("{}.{}", machineName, extension));
+    let mut index = 2;
+    while usedFileNames.contains(&fileName) {
+        fileName = PathBuf::from(format!("{}-{}.{}", machineName, index, extension));
+        index += 1;
+    }
+    usedFileNames.insert(fileName.clone());
+    fileName
+}
@@ -1,25 +1,47 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+
 pub(crate) struct CharacterReader<'a>
 {
-    text: &'a str,
-    index: usize
+    characters: Peekable<Chars<'a>>,
+    line: usize,
+    offset: usize
 }
 
 impl<'a> CharacterReader<'a>
 {
     pub(crate) fn new(text: &'a str) -> Self
     {
-        Self{text, index: 0}
+        Self{characters: text.chars().peekable(), line: 1, offset: 0}
     }
 
     pub(crate) fn next(&mut self) -> Option<char>
     {
-        let character = self.text.chars().nth(self.index);
-        self.index += 1;
+        let character = self.characters.next();
+        if let Some(character) = character {
+            self.offset += character.len_utf8();
+        }
+        if character == Some('\n') {
+            self.line += 1;
+        }
         character
     }
 
     pub(crate) fn peek(&mut self) -> Option<char>
     {
-        self.text.chars().nth(self.index)
+        self.characters.peek().copied()
+    }
+
+    /// Returns the number of the line, counted from 1, containing the character which will be returned by `next`.
+    pub(crate) fn line(&self) -> usize
+    {
+        self.line
+    }
+
+    /// Returns the byte offset, within the read text, of the character which will be returned by `next`.
+    pub(crate) fn offset(&self) -> usize
+    {
+        self.offset
     }
 }
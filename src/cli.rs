@@ -0,0 +1,689 @@
+use crate::action_sequence::ActionListLayout;
+use crate::config::{ConfigArgument, findConfigFile, loadConfigArguments, mergeArguments};
+use crate::coverage::{CoverageQuery, DEFAULT_LOG_PATTERN};
+use crate::diff::DiffOptions;
+use crate::diagram::{DiagramOptions, StateSelector};
+use crate::label_template::LabelTemplate;
+use crate::lint::{LintOptions, parseRuleSetting};
+use crate::matrix_renderer::TableFormat;
+use crate::name_policy::{NamePolicy, NamespaceStripping, loadLabels};
+use crate::name_resolver::NameDisplay;
+use crate::plantuml_renderer::{LayoutDirection, PlantUmlStyle};
+use crate::renderer::OutputFormat;
+use crate::serve::ServeOptions;
+use crate::simulator::SimulationOptions;
+use crate::state_behavior::StateBehaviorDetail;
+use crate::svg_renderer::SvgRenderer;
+use crate::test_generator::TestFramework;
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+
+pub(crate) const STDIN_PATH: &str = "-";
+
+const HELP_TEXT: &str = "\
+Generates state diagrams from C++ state machines written with boost::msm.
+
+Usage: boost-msm-grapher [COMMAND] [OPTIONS] <PATH>...
+
+Commands:
+  analyze    Report unreachable states, states without outgoing transitions, conflicting transitions and endless
+             cycles of anonymous transitions
+  path       Print the shortest sequence of events leading between two states: path <FROM> <TO> <PATH>
+  gen-tests  Generate C++ tests checking every transition
+  lint       Check naming conventions, order of rows and other rules, set with --rule
+  diff       Compare machines from two files: diff <OLD> <NEW>
+  simulate   Process events typed on standard input, starting from the initial state
+  coverage   Colour transitions by the number of times they were taken according to a log given with --log
+  lsp        Serve the Language Server Protocol on standard input and output: diagnostics, hovers on states listing
+             their transitions, going to definitions of states and events, and a msm-grapher/render request
+  serve      Show diagrams of all machines in a browser, reloaded when the files change, next to a list of machines
+             with findings of lint
+
+Arguments:
+  <PATH>...  C++ files or directories to analyze, \"-\" reads from standard input
+
+Options:
+  -f, --format <FORMAT>   Output format: plantuml (default), dot, mermaid, or a state by event table: matrix
+      --table-format <TABLE_FORMAT>
+                          Format of the matrix: markdown (default), csv, html
+  -o, --output <FILE>     Write the output to FILE instead of standard output
+  -d, --output-dir <DIR>  Write one diagram per machine into DIR
+      --watch             Regenerate diagrams whenever the input files change, printing findings of analyze, until
+                          stopped with Ctrl+C, requires --output or --output-dir
+      --names <DISPLAY>   Show names as written: alias (default), or with aliases resolved: canonical
+      --strip-namespaces  Remove namespaces from displayed names
+      --strip-namespace <PREFIX>
+                          Remove namespace PREFIX from displayed names, can be repeated
+      --template-depth <DEPTH>
+                          Replace template arguments nested deeper than DEPTH with \"...\"
+      --labels <FILE>     Display names using labels from a TOML FILE with a [labels] table
+      --action-list <LAYOUT>
+                          Show actions of a sequence: inline (default) or lines
+      --label-template <TEMPLATE>
+                          Format transition labels with TEMPLATE, where sections in braces are shown only for
+                          transitions having the part they name: event, guard or action, and \\n starts a new line,
+                          e.g. \"{event}{ [guard]}{ / action}\"
+      --hide-guards       Do not show guards in transition labels
+      --hide-actions      Do not show actions in transition labels
+      --state-behaviors <DETAIL>
+                          Show on_entry and on_exit defined in states: off (default), plain, with the first line of
+                          a comment in them: comment, or with names of functions they call: calls
+      --include <GLOB>    Analyze only files matching GLOB in directories, can be repeated
+      --exclude <GLOB>    Skip files matching GLOB in directories, can be repeated
+      --color-namespace <NAMESPACE>=<COLOR>
+                          Colour states from NAMESPACE, can be repeated
+      --color-states <REGEX>=<COLOR>
+                          Colour states with names matching REGEX, can be repeated
+      --color-event <EVENT>=<COLOR>
+                          Colour transitions taken on EVENT, can be repeated
+      --theme <NAME>      With plantuml, use the theme NAME
+      --skinparam <NAME>=<VALUE>
+                          With plantuml, set the skin parameter NAME, can be repeated
+      --direction <DIRECTION>
+                          With plantuml, lay states out: top-down (default) or left-right
+      --legend            With plantuml, show a legend of events, coloured as given with --color-event
+      --header <FILE>     With plantuml, insert the text of FILE after the beginning of the diagram
+      --footer <FILE>     With plantuml, insert the text of FILE before the end of the diagram
+      --all-paths <LENGTH>
+                          With path, list all paths without repeated states of at most LENGTH transitions
+      --highlight         With path or diff, print the diagram with the found paths or the differences highlighted
+      --framework <FRAMEWORK>
+                          With gen-tests, the test framework: gtest (default) or catch2
+      --machine <NAME>    With simulate, the machine to simulate instead of the first one found
+      --guard <NAME>=<true|false>
+                          With simulate, the outcome of guard NAME instead of asking about it, can be repeated
+      --port <PORT>       With serve, the port to listen on at 127.0.0.1, by default: 8080, 0 picks a free one
+      --svg-renderer <RENDERER>
+                          With serve, how diagrams are drawn: builtin (default) or with dot from Graphviz: dot
+      --rule <RULE>=<SEVERITY>
+                          With lint or serve, the severity of RULE: off, warning (default) or error, can be repeated.
+                          Rules: naming, unsorted-rows, duplicate-row, self-loop-without-action, unguarded-none-event,
+                          missing-initial-state. A rule can be suppressed with a comment: // msm-grapher: allow(RULE)
+      --state-naming <REGEX>
+                          With lint or serve, the pattern names of states must match, by default: ^[A-Z][A-Za-z0-9]*$
+      --event-naming <REGEX>
+                          With lint or serve, the pattern names of events must match, by default: ^[A-Z][A-Za-z0-9]*$
+      --log <FILE>        With coverage, the log with taken transitions, \"-\" reads from standard input
+      --log-pattern <REGEX>
+                          With coverage, the regular expression matching a transition in the log, with named groups:
+                          start, event and optionally target, by default:
+                          state=(?P<start>\\S+)\\s+event=(?P<event>\\S+)\\s+->\\s+state=(?P<target>\\S+)
+      --config <FILE>     Read options from FILE instead of the nearest msm-grapher.toml above the input file
+      --no-config         Do not read options from a configuration file
+  -h, --help              Print help
+  -V, --version           Print version
+
+Options can also be set in a TOML file named msm-grapher.toml, found in the directory of the last input file or above it.
+Keys are names of long options, like format = \"dot\" or strip-namespaces = true, with lists for repeated options.
+Options with NAME=VALUE settings can also be tables: skinparams, namespace-colors, state-colors and event-colors.
+Options of a single command go to a table named after it, e.g. [lint] with a [lint.rules] table of severities.
+Options given on the command line replace the ones from the file.
+
+Comments in the C++ code can enrich diagrams: // @msm-label \"NAME\" above the struct of a state displays it as NAME,
+and // @msm-note TEXT above or next to a row adds TEXT as a note to its transition.
+";
+
+pub(crate) enum Command
+{
+    PrintHelp,
+    PrintVersion,
+    Generate(Options),
+    Analyze(Options),
+    FindPath(Options, PathQuery),
+    GenerateTests(Options, TestFramework),
+    MeasureCoverage(Options, CoverageQuery),
+    Simulate(Options, SimulationOptions),
+    Diff(Options, DiffOptions),
+    Lint(Options, LintOptions),
+    RunLanguageServer(Options),
+    Serve(Options, ServeOptions)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Subcommand
+{
+    Generate,
+    Analyze,
+    FindPath,
+    GenerateTests,
+    MeasureCoverage,
+    Simulate,
+    Diff,
+    Lint,
+    RunLanguageServer,
+    Serve
+}
+
+impl Subcommand
+{
+    const ALL: [Self; 10] = [Self::Generate, Self::Analyze, Self::FindPath, Self::GenerateTests, Self::MeasureCoverage,
+        Self::Simulate, Self::Diff, Self::Lint, Self::RunLanguageServer, Self::Serve];
+
+    fn fromName(name: &str) -> Option<Self>
+    {
+        match name {
+            "analyze" => Some(Self::Analyze),
+            "path" => Some(Self::FindPath),
+            "gen-tests" => Some(Self::GenerateTests),
+            "coverage" => Some(Self::MeasureCoverage),
+            "simulate" => Some(Self::Simulate),
+            "diff" => Some(Self::Diff),
+            "lint" => Some(Self::Lint),
+            "lsp" => Some(Self::RunLanguageServer),
+            "serve" => Some(Self::Serve),
+            _ => None
+        }
+    }
+
+    fn name(self) -> &'static str
+    {
+        match self {
+            Self::Generate => "generate",
+            Self::Analyze => "analyze",
+            Self::FindPath => "path",
+            Self::GenerateTests => "gen-tests",
+            Self::MeasureCoverage => "coverage",
+            Self::Simulate => "simulate",
+            Self::Diff => "diff",
+            Self::Lint => "lint",
+            Self::RunLanguageServer => "lsp",
+            Self::Serve => "serve"
+        }
+    }
+}
+
+/// States to find paths between, given to the path command.
+pub(crate) struct PathQuery
+{
+    pub(crate) start: String,
+    pub(crate) target: String,
+    pub(crate) maxLength: Option<usize>,
+    pub(crate) highlight: bool
+}
+
+pub(crate) struct Options
+{
+    pub(crate) inputPaths: Vec<PathBuf>,
+    pub(crate) outputPath: Option<PathBuf>,
+    pub(crate) outputDirectory: Option<PathBuf>,
+    pub(crate) watches: bool,
+    pub(crate) format: OutputFormat,
+    pub(crate) nameDisplay: NameDisplay,
+    pub(crate) namespaceStripping: NamespaceStripping,
+    pub(crate) templateDepth: Option<usize>,
+    pub(crate) labelsPath: Option<PathBuf>,
+    pub(crate) actionListLayout: ActionListLayout,
+    pub(crate) labelTemplate: Option<LabelTemplate>,
+    pub(crate) hidesGuards: bool,
+    pub(crate) hidesActions: bool,
+    pub(crate) stateBehaviorDetail: StateBehaviorDetail,
+    pub(crate) includePatterns: Vec<String>,
+    pub(crate) excludePatterns: Vec<String>,
+    pub(crate) stateColors: Vec<(StateSelector, String)>,
+    pub(crate) eventColors: Vec<(String, String)>,
+    pub(crate) plantUmlStyle: PlantUmlStyle,
+    pub(crate) headerPath: Option<PathBuf>,
+    pub(crate) footerPath: Option<PathBuf>
+}
+
+impl Options
+{
+    fn new() -> Self
+    {
+        Self{
+            inputPaths: vec![],
+            outputPath: None,
+            outputDirectory: None,
+            watches: false,
+            format: OutputFormat::PlantUml,
+            nameDisplay: NameDisplay::Alias,
+            namespaceStripping: NamespaceStripping::Nothing,
+            templateDepth: None,
+            labelsPath: None,
+            actionListLayout: ActionListLayout::Inline,
+            labelTemplate: None,
+            hidesGuards: false,
+            hidesActions: false,
+            stateBehaviorDetail: StateBehaviorDetail::Off,
+            includePatterns: vec![],
+            excludePatterns: vec![],
+            stateColors: vec![],
+            eventColors: vec![],
+            plantUmlStyle: PlantUmlStyle::new(),
+            headerPath: None,
+            footerPath: None
+        }
+    }
+
+    pub(crate) fn makeDiagramOptions(&self) -> Result<DiagramOptions,String>
+    {
+        let mut namePolicy = NamePolicy::new();
+        namePolicy.namespaceStripping = self.namespaceStripping.clone();
+        namePolicy.templateDepth = self.templateDepth;
+        if let Some(labelsPath) = &self.labelsPath {
+            namePolicy.labels = loadLabels(labelsPath)?;
+        }
+        let mut plantUmlStyle = self.plantUmlStyle.clone();
+        plantUmlStyle.header = self.headerPath.as_deref().map(|path| readStyleFile("header", path)).transpose()?;
+        plantUmlStyle.footer = self.footerPath.as_deref().map(|path| readStyleFile("footer", path)).transpose()?;
+        Ok(DiagramOptions{
+            namePolicy,
+            actionListLayout: self.actionListLayout,
+            labelTemplate: self.labelTemplate.clone(),
+            hidesGuards: self.hidesGuards,
+            hidesActions: self.hidesActions,
+            stateBehaviorDetail: self.stateBehaviorDetail,
+            stateColors: self.stateColors.clone(),
+            eventColors: self.eventColors.clone(),
+            plantUmlStyle
+        })
+    }
+}
+
+fn readStyleFile(kind: &str, path: &Path) -> Result<String,String>
+{
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) => Err(format!("Failed to read {} file: {:?}, error: {}", kind, path, e))
+    }
+}
+
+pub(crate) fn helpText() -> &'static str
+{
+    HELP_TEXT
+}
+
+pub(crate) fn versionText() -> String
+{
+    format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+pub(crate) fn isStdin(path: &Path) -> bool
+{
+    path.as_os_str() == STDIN_PATH
+}
+
+pub(crate) fn parseArguments(args: &[String]) -> Result<Command,String>
+{
+    if args.is_empty() {
+        return Err("Unexpected no arguments passed to program.".into());
+    }
+
+    let (subcommand, args) = match args.get(1).and_then(|name| Subcommand::fromName(name)) {
+        Some(subcommand) => (subcommand, &args[2..]),
+        None => (Subcommand::Generate, &args[1..])
+    };
+    let mut parser = ArgumentParser::new(subcommand, args);
+    if let Some(command) = parser.parseOptions()? {
+        return Ok(command);
+    }
+    let configPath = match parser.findConfigFile() {
+        Some(configPath) => configPath,
+        None => return parser.finish()
+    };
+
+    // Options shared by some commands can be given once at the top level, so there they are skipped by other commands.
+    let configArguments: Vec<ConfigArgument> = loadConfigArguments(&configPath, subcommand.name())?.into_iter()
+        .filter(|argument| !argument.isShared || acceptsOption(subcommand, &argument.text)
+            || !Subcommand::ALL.iter().any(|&otherSubcommand| acceptsOption(otherSubcommand, &argument.text)))
+        .collect();
+    let configArgumentTexts: Vec<String> = configArguments.iter().map(|argument| argument.text.clone()).collect();
+    if let Err(e) = ArgumentParser::new(subcommand, &configArgumentTexts).parseOptions() {
+        return Err(format!("{}: {}", configPath.display(), e));
+    }
+    let mergedArguments = mergeArguments(configArguments, args);
+    let mut parser = ArgumentParser::new(subcommand, &mergedArguments);
+    if let Some(command) = parser.parseOptions()? {
+        return Ok(command);
+    }
+    parser.finish()
+}
+
+/// Tells whether the command has the option, whether its value is valid or not.
+fn acceptsOption(subcommand: Subcommand, argument: &str) -> bool
+{
+    let arguments = [argument.to_string()];
+    let mut parser = ArgumentParser::new(subcommand, &arguments);
+    parser.skipsUnknownOptions = true;
+    let _ = parser.parseOptions();
+    !parser.hasSkippedOption
+}
+
+struct ArgumentParser<'a>
+{
+    subcommand: Subcommand,
+    args: std::slice::Iter<'a, String>,
+    options: Options,
+    tableFormat: Option<TableFormat>,
+    pathQuery: PathQuery,
+    testFramework: TestFramework,
+    coverageQuery: CoverageQuery,
+    simulationOptions: SimulationOptions,
+    lintOptions: LintOptions,
+    serveOptions: ServeOptions,
+    highlight: bool,
+    configPath: Option<PathBuf>,
+    ignoresConfig: bool,
+    onlyPositionalArguments: bool,
+    skipsUnknownOptions: bool,
+    hasSkippedOption: bool
+}
+
+impl<'a> ArgumentParser<'a>
+{
+    fn new(subcommand: Subcommand, args: &'a [String]) -> Self
+    {
+        Self{
+            subcommand,
+            args: args.iter(),
+            options: Options::new(),
+            tableFormat: None,
+            pathQuery: PathQuery{start: String::new(), target: String::new(), maxLength: None, highlight: false},
+            testFramework: TestFramework::GoogleTest,
+            coverageQuery: CoverageQuery{logPath: None, logPattern: DEFAULT_LOG_PATTERN.into()},
+            simulationOptions: SimulationOptions{machineName: None, guardAnswers: vec![]},
+            lintOptions: LintOptions::new(),
+            serveOptions: ServeOptions::new(),
+            highlight: false,
+            configPath: None,
+            ignoresConfig: false,
+            onlyPositionalArguments: false,
+            skipsUnknownOptions: false,
+            hasSkippedOption: false
+        }
+    }
+
+    /// Parses the arguments, returning a command only when it does not need further processing, like printing help.
+    fn parseOptions(&mut self) -> Result<Option<Command>,String>
+    {
+        while let Some(arg) = self.args.next() {
+            if self.onlyPositionalArguments || arg == STDIN_PATH || !arg.starts_with('-') {
+                self.options.inputPaths.push(PathBuf::from(arg));
+                continue;
+            }
+
+            let (name, inlineValue) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None)
+            };
+            match name {
+                "--" => self.onlyPositionalArguments = true,
+                "-h" | "--help" => return Ok(Some(Command::PrintHelp)),
+                "-V" | "--version" => return Ok(Some(Command::PrintVersion)),
+                "--config" => self.configPath = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "--no-config" => self.ignoresConfig = true,
+                "-f" | "--format" => self.options.format = OutputFormat::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--table-format" => self.tableFormat = Some(TableFormat::fromName(&self.takeValue(name, inlineValue)?)?),
+                "-o" | "--output" => self.options.outputPath = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "-d" | "--output-dir" => self.options.outputDirectory = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "--watch" if self.subcommand == Subcommand::Generate => self.options.watches = true,
+                "--names" => self.options.nameDisplay = NameDisplay::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--strip-namespaces" => self.options.namespaceStripping = NamespaceStripping::All,
+                "--strip-namespace" => {
+                    let prefix = self.takeValue(name, inlineValue)?;
+                    match &mut self.options.namespaceStripping {
+                        NamespaceStripping::Prefixes(prefixes) => prefixes.push(prefix),
+                        NamespaceStripping::All => (),
+                        NamespaceStripping::Nothing => self.options.namespaceStripping = NamespaceStripping::Prefixes(vec![prefix])
+                    }
+                },
+                "--template-depth" => self.options.templateDepth = Some(parseNumber(name, &self.takeValue(name, inlineValue)?)?),
+                "--labels" => self.options.labelsPath = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "--action-list" =>
+                    self.options.actionListLayout = ActionListLayout::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--label-template" =>
+                    self.options.labelTemplate = Some(LabelTemplate::parse(&self.takeValue(name, inlineValue)?)?),
+                "--hide-guards" => self.options.hidesGuards = true,
+                "--hide-actions" => self.options.hidesActions = true,
+                "--state-behaviors" =>
+                    self.options.stateBehaviorDetail = StateBehaviorDetail::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--include" => {
+                    let pattern = self.takeValue(name, inlineValue)?;
+                    self.options.includePatterns.push(pattern);
+                },
+                "--exclude" => {
+                    let pattern = self.takeValue(name, inlineValue)?;
+                    self.options.excludePatterns.push(pattern);
+                },
+                "--color-namespace" => {
+                    let (namespace, color) = parseSetting(name, &self.takeValue(name, inlineValue)?)?;
+                    self.options.stateColors.push((StateSelector::Namespace(namespace), color));
+                },
+                "--color-states" => {
+                    let (pattern, color) = parseStateColor(&self.takeValue(name, inlineValue)?)?;
+                    self.options.stateColors.push((StateSelector::Pattern(pattern), color));
+                },
+                "--color-event" => {
+                    let eventColor = parseSetting(name, &self.takeValue(name, inlineValue)?)?;
+                    self.options.eventColors.push(eventColor);
+                },
+                "--theme" => self.options.plantUmlStyle.theme = Some(self.takeValue(name, inlineValue)?),
+                "--skinparam" => {
+                    let skinparam = parseSetting(name, &self.takeValue(name, inlineValue)?)?;
+                    self.options.plantUmlStyle.skinparams.push(skinparam);
+                },
+                "--direction" =>
+                    self.options.plantUmlStyle.direction = LayoutDirection::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--legend" => self.options.plantUmlStyle.showsLegend = true,
+                "--header" => self.options.headerPath = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "--footer" => self.options.footerPath = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "--all-paths" if self.subcommand == Subcommand::FindPath =>
+                    self.pathQuery.maxLength = Some(parseNumber(name, &self.takeValue(name, inlineValue)?)?),
+                "--highlight" if matches!(self.subcommand, Subcommand::FindPath | Subcommand::Diff) => self.highlight = true,
+                "--framework" if self.subcommand == Subcommand::GenerateTests =>
+                    self.testFramework = TestFramework::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--log" if self.subcommand == Subcommand::MeasureCoverage =>
+                    self.coverageQuery.logPath = Some(PathBuf::from(self.takeValue(name, inlineValue)?)),
+                "--log-pattern" if self.subcommand == Subcommand::MeasureCoverage =>
+                    self.coverageQuery.logPattern = self.takeValue(name, inlineValue)?,
+                "--machine" if self.subcommand == Subcommand::Simulate =>
+                    self.simulationOptions.machineName = Some(self.takeValue(name, inlineValue)?),
+                "--guard" if self.subcommand == Subcommand::Simulate => {
+                    let answer = parseGuardAnswer(&self.takeValue(name, inlineValue)?)?;
+                    self.simulationOptions.guardAnswers.push(answer);
+                },
+                "--port" if self.subcommand == Subcommand::Serve =>
+                    self.serveOptions.port = parsePort(&self.takeValue(name, inlineValue)?)?,
+                "--svg-renderer" if self.subcommand == Subcommand::Serve =>
+                    self.serveOptions.svgRenderer = SvgRenderer::fromName(&self.takeValue(name, inlineValue)?)?,
+                "--rule" if matches!(self.subcommand, Subcommand::Lint | Subcommand::Serve) => {
+                    let setting = parseRuleSetting(&self.takeValue(name, inlineValue)?)?;
+                    self.lintOptions.severities.push(setting);
+                },
+                "--state-naming" if matches!(self.subcommand, Subcommand::Lint | Subcommand::Serve) =>
+                    self.lintOptions.stateNaming = self.takeValue(name, inlineValue)?,
+                "--event-naming" if matches!(self.subcommand, Subcommand::Lint | Subcommand::Serve) =>
+                    self.lintOptions.eventNaming = self.takeValue(name, inlineValue)?,
+                _ if self.skipsUnknownOptions => self.hasSkippedOption = true,
+                _ => return Err(format!("Unknown option: {}, use --help to list available options.", name))
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the configuration file given with --config, or the one found above the last input file, or above the
+    /// current directory when standard input is read.
+    fn findConfigFile(&self) -> Option<PathBuf>
+    {
+        if self.ignoresConfig {
+            return None;
+        }
+        if let Some(configPath) = &self.configPath {
+            return Some(configPath.clone());
+        }
+        match self.options.inputPaths.iter().rfind(|path| !isStdin(path)) {
+            Some(inputPath) => findConfigFile(inputPath),
+            None => findConfigFile(Path::new("."))
+        }
+    }
+
+    fn finish(mut self) -> Result<Command,String>
+    {
+        self.applyTableFormat()?;
+        if self.subcommand == Subcommand::FindPath {
+            self.takeStatesOfPathQuery()?;
+        }
+        self.validate()?;
+        match self.subcommand {
+            Subcommand::Generate => Ok(Command::Generate(self.options)),
+            Subcommand::Analyze => Ok(Command::Analyze(self.options)),
+            Subcommand::FindPath => Ok(Command::FindPath(self.options, PathQuery{highlight: self.highlight, ..self.pathQuery})),
+            Subcommand::GenerateTests => Ok(Command::GenerateTests(self.options, self.testFramework)),
+            Subcommand::MeasureCoverage => Ok(Command::MeasureCoverage(self.options, self.coverageQuery)),
+            Subcommand::Simulate => Ok(Command::Simulate(self.options, self.simulationOptions)),
+            Subcommand::Diff => Ok(Command::Diff(self.options, DiffOptions{highlight: self.highlight})),
+            Subcommand::Lint => Ok(Command::Lint(self.options, self.lintOptions)),
+            Subcommand::RunLanguageServer => Ok(Command::RunLanguageServer(self.options)),
+            Subcommand::Serve => Ok(Command::Serve(self.options, ServeOptions{lintOptions: self.lintOptions, ..self.serveOptions}))
+        }
+    }
+
+    fn takeValue(&mut self, name: &str, inlineValue: Option<String>) -> Result<String,String>
+    {
+        if let Some(value) = inlineValue {
+            return Ok(value);
+        }
+        match self.args.next() {
+            Some(value) => Ok(value.clone()),
+            None => Err(format!("Option {} requires a value.", name))
+        }
+    }
+
+    fn applyTableFormat(&mut self) -> Result<(),String>
+    {
+        match (self.tableFormat, &mut self.options.format) {
+            (Some(tableFormat), OutputFormat::Matrix(format)) => *format = tableFormat,
+            (Some(_), _) => return Err("Option --table-format requires --format matrix.".into()),
+            (None, _) => ()
+        }
+        Ok(())
+    }
+
+    fn takeStatesOfPathQuery(&mut self) -> Result<(),String>
+    {
+        let inputPaths = &mut self.options.inputPaths;
+        if inputPaths.len() != 3 {
+            return Err("Command path requires a start state, a target state and a file, e.g.: path Idle Error machine.cpp.".into());
+        }
+        self.pathQuery.start = inputPaths.remove(0).to_string_lossy().into();
+        self.pathQuery.target = inputPaths.remove(0).to_string_lossy().into();
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(),String>
+    {
+        let options = &self.options;
+        if self.subcommand == Subcommand::RunLanguageServer {
+            if !options.inputPaths.is_empty() {
+                return Err("Command lsp receives documents from the editor, so it does not take paths.".into());
+            }
+            if options.outputPath.is_some() || options.outputDirectory.is_some() {
+                return Err("Command lsp answers on standard output, so it cannot write to files.".into());
+            }
+            return Ok(());
+        }
+        if options.inputPaths.is_empty() {
+            return Err("Please provide a path to a file to analyze.".into());
+        }
+        if self.subcommand != Subcommand::Generate {
+            if options.outputDirectory.is_some() {
+                return Err(format!("Option --output-dir cannot be used with the {} command.", self.subcommand.name()));
+            }
+            match self.subcommand {
+                Subcommand::Analyze | Subcommand::Lint | Subcommand::Serve => (),
+                Subcommand::Diff => if options.inputPaths.len() != 2 || options.inputPaths.iter().any(|path| path.is_dir()) {
+                    return Err("Command diff requires two files, e.g.: diff old.cpp new.cpp.".into());
+                } else if options.inputPaths.iter().all(|path| isStdin(path)) {
+                    return Err("Only one of the compared files can be read from standard input.".into());
+                },
+                _ => if options.inputPaths.len() > 1 || options.inputPaths[0].is_dir() {
+                    return Err(format!("Command {} requires a single file.", self.subcommand.name()));
+                }
+            }
+            if self.subcommand == Subcommand::Simulate && isStdin(&options.inputPaths[0]) {
+                return Err("Command simulate reads events from standard input, so the file cannot be read from it.".into());
+            }
+            if self.subcommand == Subcommand::Serve {
+                if options.inputPaths.iter().any(|path| isStdin(path)) {
+                    return Err("Command serve reads files again when they change, so they cannot be read from standard input.".into());
+                }
+                if options.outputPath.is_some() {
+                    return Err("Command serve shows diagrams in a browser, so it cannot write them to a file.".into());
+                }
+            }
+            return Ok(());
+        }
+        if options.watches {
+            if options.inputPaths.iter().any(|path| isStdin(path)) {
+                return Err("Option --watch cannot watch standard input.".into());
+            }
+            if options.outputPath.is_none() && options.outputDirectory.is_none() {
+                return Err("Option --watch requires --output or --output-dir.".into());
+            }
+        }
+        match &options.outputDirectory {
+            Some(_) => {
+                if options.outputPath.is_some() {
+                    return Err("Options --output and --output-dir cannot be used together.".into());
+                }
+                if options.inputPaths.iter().any(|path| isStdin(path)) {
+                    return Err("Standard input cannot be analyzed together with --output-dir.".into());
+                }
+            },
+            None => {
+                if options.inputPaths.len() > 1 || options.inputPaths[0].is_dir() {
+                    return Err("Please provide an output directory with --output-dir when analyzing multiple files or directories.".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parseNumber(option: &str, value: &str) -> Result<usize,String>
+{
+    match value.parse() {
+        Ok(number) => Ok(number),
+        Err(_) => Err(format!("Option {} requires a non-negative number, got: {}.", option, value))
+    }
+}
+
+fn parsePort(value: &str) -> Result<u16,String>
+{
+    match value.parse() {
+        Ok(port) => Ok(port),
+        Err(_) => Err(format!("Option --port requires a port number, got: {}.", value))
+    }
+}
+
+fn parseSetting(option: &str, value: &str) -> Result<(String, String),String>
+{
+    match value.split_once('=') {
+        Some((name, setting)) if !name.is_empty() && !setting.is_empty() => Ok((name.into(), setting.into())),
+        _ => Err(format!("Option {} requires a value like NAME=VALUE, got: {}.", option, value))
+    }
+}
+
+/// Parses `REGEX=COLOR`, splitting at the last `=` as the regular expression can contain one.
+fn parseStateColor(value: &str) -> Result<(Regex, String),String>
+{
+    let (pattern, color) = match value.rsplit_once('=') {
+        Some((pattern, color)) if !pattern.is_empty() && !color.is_empty() => (pattern, color),
+        _ => return Err(format!("Option --color-states requires a value like REGEX=COLOR, got: {}.", value))
+    };
+    match Regex::new(pattern) {
+        Ok(pattern) => Ok((pattern, color.into())),
+        Err(e) => Err(format!("Invalid regular expression in --color-states: {}, error: {}", pattern, e))
+    }
+}
+
+fn parseGuardAnswer(value: &str) -> Result<(String, bool),String>
+{
+    match value.split_once('=') {
+        Some((name, "true")) if !name.is_empty() => Ok((name.into(), true)),
+        Some((name, "false")) if !name.is_empty() => Ok((name.into(), false)),
+        _ => Err(format!("Option --guard requires a value like NAME=true or NAME=false, got: {}.", value))
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+
+
+const CONFIG_FILE_NAME: &str = "msm-grapher.toml";
+
+/// Options whose values are paths, resolved relative to the directory of the configuration file.
+const PATH_OPTIONS: [&str; 6] = ["labels", "output", "output-dir", "log", "header", "footer"];
+
+/// Tables of settings, each becoming an option with a `NAME=VALUE` value.
+const TABLE_OPTIONS: [(&str, &str); 6] = [
+    ("rules", "rule"),
+    ("guards", "guard"),
+    ("skinparams", "skinparam"),
+    ("namespace-colors", "color-namespace"),
+    ("state-colors", "color-states"),
+    ("event-colors", "color-event")
+];
+
+/// Options which can be repeated to give settings of different names, so the ones from the command line are added to
+/// the ones from the configuration file instead of replacing them.
+const MERGED_OPTIONS: [&str; 6] = ["--rule", "--guard", "--skinparam", "--color-namespace", "--color-states", "--color-event"];
+
+const SHORT_OPTIONS: [(&str, &str); 3] = [("-f", "--format"), ("-o", "--output"), ("-d", "--output-dir")];
+
+/// An option read from the configuration file, written as it would be on the command line.
+pub(crate) struct ConfigArgument
+{
+    pub(crate) name: String,
+    pub(crate) text: String,
+    /// Whether the option was given at the top level, for all commands, instead of in the table of the command.
+    pub(crate) isShared: bool
+}
+
+/// Looks for the configuration file in the directory of the input path and in the directories above it.
+pub(crate) fn findConfigFile(inputPath: &Path) -> Option<PathBuf>
+{
+    let inputPath = std::path::absolute(inputPath).ok()?;
+    let mut directory = match inputPath.is_dir() {
+        true => inputPath.as_path(),
+        false => inputPath.parent()?
+    };
+    loop {
+        let configPath = directory.join(CONFIG_FILE_NAME);
+        if configPath.is_file() {
+            return Some(configPath);
+        }
+        directory = directory.parent()?;
+    }
+}
+
+/// Reads options from the top level of the configuration file, shared by the commands which have them, and from the
+/// table named after the command. Options with `NAME=VALUE` settings, like the `rules` of lint, can also be given as
+/// tables.
+pub(crate) fn loadConfigArguments(path: &Path, commandName: &str) -> Result<Vec<ConfigArgument>,String>
+{
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return Err(format!("Failed to read configuration file: {:?}, error: {}", path, e))
+    };
+    let table: toml::Table = match content.parse() {
+        Ok(table) => table,
+        Err(e) => return Err(format!("Failed to parse configuration file: {:?}, error: {}", path, e))
+    };
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut arguments = vec![];
+    for (key, value) in table.iter().filter(|(key, value)| !value.is_table() || isTableOption(key)) {
+        let settingArguments = convertSetting(key, value, directory, path)?;
+        arguments.extend(settingArguments.into_iter().map(|argument| ConfigArgument{isShared: true, ..argument}));
+    }
+    if let Some(commandTable) = table.get(commandName) {
+        let commandTable = match commandTable {
+            toml::Value::Table(commandTable) => commandTable,
+            _ => return Err(format!("Setting {} in configuration file {:?} should be a table.", commandName, path))
+        };
+        for (key, value) in commandTable {
+            arguments.extend(convertSetting(key, value, directory, path)?);
+        }
+    }
+    Ok(arguments)
+}
+
+fn isTableOption(key: &str) -> bool
+{
+    TABLE_OPTIONS.iter().any(|(tableName, _)| *tableName == key)
+}
+
+fn convertSetting(key: &str, value: &toml::Value, directory: &Path, path: &Path) -> Result<Vec<ConfigArgument>,String>
+{
+    if let Some((_, optionName)) = TABLE_OPTIONS.iter().find(|(tableName, _)| *tableName == key) {
+        let settings = match value {
+            toml::Value::Table(settings) => settings,
+            _ => return Err(format!("Setting {} in configuration file {:?} should be a table.", key, path))
+        };
+        return settings.iter()
+            .map(|(name, value)| Ok(makeArgument(optionName, Some(format!("{}={}", name, convertScalar(key, value, path)?)))))
+            .collect();
+    }
+
+    let values = match value {
+        toml::Value::Boolean(true) => return Ok(vec![makeArgument(key, None)]),
+        toml::Value::Boolean(false) => return Ok(vec![]),
+        toml::Value::Array(values) => values.iter().collect(),
+        value => vec![value]
+    };
+    values.into_iter()
+        .map(|value| {
+            let value = convertScalar(key, value, path)?;
+            match PATH_OPTIONS.contains(&key) {
+                true => Ok(makeArgument(key, Some(directory.join(value).to_string_lossy().into_owned()))),
+                false => Ok(makeArgument(key, Some(value)))
+            }
+        })
+        .collect()
+}
+
+fn convertScalar(key: &str, value: &toml::Value, path: &Path) -> Result<String,String>
+{
+    match value {
+        toml::Value::String(text) => Ok(text.clone()),
+        toml::Value::Integer(number) => Ok(number.to_string()),
+        toml::Value::Boolean(flag) => Ok(flag.to_string()),
+        _ => Err(format!("Setting {} in configuration file {:?} should be a string, a number or a boolean.", key, path))
+    }
+}
+
+fn makeArgument(key: &str, value: Option<String>) -> ConfigArgument
+{
+    let name = format!("--{}", key);
+    let text = match value {
+        Some(value) => format!("{}={}", name, value),
+        None => name.clone()
+    };
+    ConfigArgument{name, text, isShared: false}
+}
+
+/// Places options from the configuration file before the ones from the command line, skipping the options given on
+/// the command line unless they are merged.
+pub(crate) fn mergeArguments(configArguments: Vec<ConfigArgument>, commandLineArguments: &[String]) -> Vec<String>
+{
+    let givenOptions = listGivenOptions(commandLineArguments);
+    configArguments.into_iter()
+        .filter(|argument| MERGED_OPTIONS.contains(&argument.name.as_str()) || !givenOptions.contains(&argument.name.as_str()))
+        .map(|argument| argument.text)
+        .chain(commandLineArguments.iter().cloned())
+        .collect()
+}
+
+fn listGivenOptions(arguments: &[String]) -> Vec<&str>
+{
+    let mut options = vec![];
+    for argument in arguments {
+        if argument == "--" {
+            break;
+        }
+        let name = argument.split_once('=').map_or(argument.as_str(), |(name, _)| name);
+        match SHORT_OPTIONS.iter().find(|(shortName, _)| *shortName == name) {
+            Some((_, longName)) => options.push(*longName),
+            None if name.starts_with("--") => options.push(name),
+            None => ()
+        }
+    }
+    options
+}
@@ -0,0 +1,117 @@
+use crate::analysis::displayPath;
+use crate::cli::{Options, isStdin};
+use crate::diagram::{DiagramOptions, buildDiagram};
+use crate::input_files::readInputFile;
+use crate::machine::{Machine, parseMachines};
+use crate::renderer::renderDiagram;
+
+use regex::Regex;
+use std::path::PathBuf;
+
+
+pub(crate) const DEFAULT_LOG_PATTERN: &str = r"state=(?P<start>\S+)\s+event=(?P<event>\S+)\s+->\s+state=(?P<target>\S+)";
+const NEVER_TAKEN_COLOR: &str = "red";
+const RARELY_TAKEN_COLOR: &str = "orange";
+const OFTEN_TAKEN_COLOR: &str = "green";
+
+/// Log to measure coverage with, given to the coverage command.
+pub(crate) struct CoverageQuery
+{
+    pub(crate) logPath: Option<PathBuf>,
+    pub(crate) logPattern: String
+}
+
+pub(crate) struct CoverageReport
+{
+    pub(crate) diagrams: String,
+    pub(crate) summary: String
+}
+
+/// Counts how many times each row was taken according to the log and renders diagrams with transitions coloured by
+/// that count. The summary lists log entries which do not match any row and the percentage of rows taken.
+pub(crate) fn measureCoverage(options: &Options, query: &CoverageQuery) -> Result<CoverageReport,String>
+{
+    let logPath = match &query.logPath {
+        Some(path) => path,
+        None => return Err("Command coverage requires a log file given with --log.".into())
+    };
+    let logPattern = match Regex::new(&query.logPattern) {
+        Ok(regex) => regex,
+        Err(e) => return Err(format!("Invalid log pattern: {}", e))
+    };
+    if !["start", "event"].iter().all(|group| logPattern.capture_names().flatten().any(|name| name == *group)) {
+        return Err("Log pattern must contain named groups: start and event, and optionally: target.".into());
+    }
+    if isStdin(logPath) && isStdin(&options.inputPaths[0]) {
+        return Err("The log and the analyzed file cannot be both read from standard input.".into());
+    }
+
+    let fileContent = readInputFile(&options.inputPaths[0])?;
+    let machines = parseMachines(&fileContent, options.nameDisplay)?;
+    if machines.is_empty() {
+        return Err("Transition table was not found.".into());
+    }
+    let diagramOptions = options.makeDiagramOptions()?;
+    let mut hitCounts: Vec<Vec<usize>> = machines.iter().map(|machine| vec![0; machine.rows.len()]).collect();
+
+    let mut summary = String::new();
+    let log = readInputFile(logPath)?;
+    for (lineIndex, line) in log.lines().enumerate() {
+        let captures = match logPattern.captures(line) {
+            Some(captures) => captures,
+            None => continue
+        };
+        let start = &captures["start"];
+        let event = &captures["event"];
+        let target = captures.name("target").map(|target| target.as_str());
+        let namePolicy = &diagramOptions.namePolicy;
+        let mut matchedRows = vec![];
+        for (machineIndex, machine) in machines.iter().enumerate() {
+            for (rowIndex, row) in machine.rows.iter().enumerate() {
+                if namePolicy.isMatch(&row.start, start) && namePolicy.isMatch(&row.event, event)
+                    && target.is_none_or(|target| namePolicy.isMatch(&row.target, target)) {
+                    matchedRows.push((machineIndex, rowIndex));
+                }
+            }
+        }
+        // Entries matching rows which differ only by guards do not tell which one was taken, so they are not counted.
+        match matchedRows.as_slice() {
+            [] => summary += &format!("{}:{}: unmatched log entry: {}\n",
+                displayPath(logPath), lineIndex + 1, line.trim()),
+            [(machineIndex, rowIndex)] => hitCounts[*machineIndex][*rowIndex] += 1,
+            _ => summary += &format!("{}:{}: ambiguous log entry matching {} transitions: {}\n",
+                displayPath(logPath), lineIndex + 1, matchedRows.len(), line.trim())
+        }
+    }
+
+    let rowCount: usize = hitCounts.iter().map(|counts| counts.len()).sum();
+    let takenRowCount = hitCounts.iter().flatten().filter(|&&count| count > 0).count();
+    summary += &format!("Coverage: {} of {} transitions taken ({:.1}%).\n",
+        takenRowCount, rowCount, 100.0 * takenRowCount as f64 / rowCount as f64);
+    let diagrams: Vec<String> = machines.iter().zip(&hitCounts)
+        .map(|(machine, machineHitCounts)| renderCoverageDiagram(machine, machineHitCounts, options, &diagramOptions))
+        .collect();
+    Ok(CoverageReport{diagrams: diagrams.join("\n") + "\n", summary})
+}
+
+fn renderCoverageDiagram(machine: &Machine, hitCounts: &[usize], options: &Options, diagramOptions: &DiagramOptions)
+    -> String
+{
+    let maxHitCount = hitCounts.iter().copied().max().unwrap_or_default();
+    let mut diagram = buildDiagram(machine, diagramOptions);
+    for (transition, &hitCount) in diagram.transitions.iter_mut().zip(hitCounts) {
+        transition.labelLines.push(format!("{} hit(s)", hitCount));
+        transition.color = Some(selectColor(hitCount, maxHitCount).into());
+    }
+    renderDiagram(&diagram, diagramOptions, options.format)
+}
+
+/// Marks never taken transitions red, and the ones taken less than a tenth as often as the most frequent one orange.
+fn selectColor(hitCount: usize, maxHitCount: usize) -> &'static str
+{
+    match hitCount {
+        0 => NEVER_TAKEN_COLOR,
+        _ if hitCount * 10 < maxHitCount => RARELY_TAKEN_COLOR,
+        _ => OFTEN_TAKEN_COLOR
+    }
+}
@@ -0,0 +1,159 @@
+use crate::action_sequence::ActionListLayout;
+use crate::analysis::unqualifiedName;
+use crate::annotations::findRowNote;
+use crate::label_template::LabelTemplate;
+use crate::machine::Machine;
+use crate::name_policy::NamePolicy;
+use crate::plantuml_renderer::PlantUmlStyle;
+use crate::state_behavior::{StateBehaviorDetail, findStateBehavior};
+use crate::transition_label::TransitionLabel;
+
+use regex::Regex;
+
+
+pub(crate) struct Diagram
+{
+    pub(crate) name: String,
+    pub(crate) initialStates: Vec<String>,
+    pub(crate) states: Vec<DiagramState>,
+    pub(crate) transitions: Vec<DiagramTransition>,
+    /// Shown events in the order of their first appearance, with colours given to them by `DiagramOptions::eventColors`.
+    pub(crate) events: Vec<DiagramEvent>
+}
+
+pub(crate) struct DiagramOptions
+{
+    pub(crate) namePolicy: NamePolicy,
+    pub(crate) actionListLayout: ActionListLayout,
+    pub(crate) labelTemplate: Option<LabelTemplate>,
+    pub(crate) hidesGuards: bool,
+    pub(crate) hidesActions: bool,
+    pub(crate) stateBehaviorDetail: StateBehaviorDetail,
+    /// Colours of states, where a later match takes precedence over an earlier one.
+    pub(crate) stateColors: Vec<(StateSelector, String)>,
+    /// Colours of transitions taken on the given events, where a later match takes precedence over an earlier one.
+    pub(crate) eventColors: Vec<(String, String)>,
+    pub(crate) plantUmlStyle: PlantUmlStyle
+}
+
+#[derive(Clone)]
+pub(crate) enum StateSelector
+{
+    Namespace(String),
+    Pattern(Regex)
+}
+
+impl StateSelector
+{
+    fn isMatch(&self, name: &str) -> bool
+    {
+        match self {
+            Self::Namespace(namespace) =>
+                name.trim_start_matches("::").starts_with(&format!("{}::", namespace.trim_start_matches("::"))),
+            Self::Pattern(pattern) => pattern.is_match(name)
+        }
+    }
+}
+
+pub(crate) struct DiagramState
+{
+    pub(crate) id: String,
+    pub(crate) label: String,
+    /// A colour name like `red` or a hex code like `#ff0000` to emphasize the state with.
+    pub(crate) color: Option<String>,
+    /// Lines shown in the box of the state below its name, like `entry / startTimer`.
+    pub(crate) descriptions: Vec<String>
+}
+
+pub(crate) struct DiagramTransition
+{
+    pub(crate) start: String,
+    pub(crate) target: String,
+    pub(crate) label: TransitionLabel,
+    pub(crate) labelLines: Vec<String>,
+    pub(crate) color: Option<String>,
+    /// Text of `@msm-note` annotations of the row.
+    pub(crate) note: Option<String>
+}
+
+pub(crate) struct DiagramEvent
+{
+    pub(crate) label: String,
+    pub(crate) color: Option<String>
+}
+
+pub(crate) fn buildDiagram(machine: &Machine, options: &DiagramOptions) -> Diagram
+{
+    let namePolicy = &options.namePolicy;
+    let mut diagram = Diagram{
+        name: machine.name.clone(),
+        initialStates: machine.initialStates.clone(),
+        states: vec![],
+        transitions: vec![],
+        events: vec![]
+    };
+    for row in &machine.rows {
+        diagram.addState(&row.start, machine, options);
+        diagram.addState(&row.target, machine, options);
+        let label = TransitionLabel::new(row, options);
+        let color = options.eventColors.iter().rev()
+            .find(|(event, _)| namePolicy.isMatch(&row.event, event))
+            .map(|(_, color)| color.clone());
+        if let Some(event) = &label.event
+            && !diagram.events.iter().any(|shownEvent| shownEvent.label == *event) {
+            diagram.events.push(DiagramEvent{label: event.clone(), color: color.clone()});
+        }
+        diagram.transitions.push(DiagramTransition{
+            start: row.start.clone(),
+            target: row.target.clone(),
+            labelLines: label.makeDiagramLines(options),
+            label,
+            color,
+            note: findRowNote(&row.comments)
+        });
+    }
+    for state in &machine.initialStates {
+        diagram.addState(state, machine, options);
+    }
+    for state in &mut diagram.states {
+        state.color = options.stateColors.iter().rev()
+            .find(|(selector, _)| selector.isMatch(&state.id))
+            .map(|(_, color)| color.clone());
+    }
+    diagram
+}
+
+impl Diagram
+{
+    /// Adds the state unless it is already present, labelled with the label given in options, or in an annotation.
+    fn addState(&mut self, name: &str, machine: &Machine, options: &DiagramOptions)
+    {
+        if self.states.iter().any(|state| state.id == name) {
+            return;
+        }
+        let namePolicy = &options.namePolicy;
+        let label = match (namePolicy.findLabel(name), machine.stateLabels.get(unqualifiedName(name))) {
+            (None, Some(label)) => label.clone(),
+            _ => namePolicy.apply(name)
+        };
+        let descriptions = match findStateBehavior(&machine.stateBehaviors, &machine.qualifiedName, name) {
+            Some(behavior) => behavior.describe(options.stateBehaviorDetail),
+            None => vec![]
+        };
+        self.states.push(DiagramState{id: name.into(), label, color: None, descriptions});
+    }
+
+    /// Colours the states with the given names.
+    pub(crate) fn colorStates(&mut self, names: &[&str], color: &str)
+    {
+        for state in self.states.iter_mut().filter(|state| names.contains(&state.id.as_str())) {
+            state.color = Some(color.into());
+        }
+    }
+}
+
+/// Replaces characters which cannot be a part of an identifier in diagram languages.
+pub(crate) fn makeIdentifier(name: &str) -> String
+{
+    name.chars().map(|character| if character.is_alphanumeric() || character == '_' { character } else { '_' }).collect()
+}
@@ -0,0 +1,188 @@
+use crate::action_sequence::ActionListLayout;
+use crate::analysis::{displayPath, listStates};
+use crate::cli::Options;
+use crate::diagram::{DiagramOptions, buildDiagram};
+use crate::input_files::readInputFile;
+use crate::machine::{Machine, parseMachines};
+use crate::renderer::renderDiagram;
+use crate::row::Row;
+use crate::transition_label::TransitionLabel;
+
+use std::path::Path;
+
+
+const ADDED_COLOR: &str = "green";
+const REMOVED_COLOR: &str = "red";
+const CHANGED_COLOR: &str = "orange";
+
+/// Settings of the diff command.
+pub(crate) struct DiffOptions
+{
+    pub(crate) highlight: bool
+}
+
+/// Differences between two versions of a machine. Rows are identified by their start state, event and target state.
+struct MachineDiff<'a>
+{
+    name: String,
+    addedStates: Vec<&'a str>,
+    removedStates: Vec<&'a str>,
+    addedRows: Vec<&'a Row>,
+    removedRows: Vec<&'a Row>,
+    changedRows: Vec<(&'a Row, &'a Row)>
+}
+
+impl MachineDiff<'_>
+{
+    fn isEmpty(&self) -> bool
+    {
+        self.addedStates.is_empty() && self.removedStates.is_empty() && self.addedRows.is_empty()
+            && self.removedRows.is_empty() && self.changedRows.is_empty()
+    }
+}
+
+/// Compares machines from two files, matched by name, and describes the differences or renders them in diagrams.
+pub(crate) fn compareFiles(options: &Options, diffOptions: &DiffOptions) -> Result<String,String>
+{
+    let oldMachines = readMachines(&options.inputPaths[0], options)?;
+    let newMachines = readMachines(&options.inputPaths[1], options)?;
+    let diagramOptions = options.makeDiagramOptions()?;
+
+    let mut output = vec![];
+    for oldMachine in oldMachines.iter().filter(|old| !newMachines.iter().any(|new| new.name == old.name)) {
+        output.push(format!("- machine {}\n", oldMachine.name));
+    }
+    for newMachine in &newMachines {
+        let oldMachine = match oldMachines.iter().find(|old| old.name == newMachine.name) {
+            Some(oldMachine) => oldMachine,
+            None => {
+                output.push(format!("+ machine {}\n", newMachine.name));
+                continue;
+            }
+        };
+        let diff = compareMachines(oldMachine, newMachine);
+        match diffOptions.highlight {
+            true => output.push(renderDiff(newMachine, &diff, options, &diagramOptions) + "\n"),
+            false if !diff.isEmpty() => output.push(describeDiff(&diff, &diagramOptions)),
+            false => ()
+        }
+    }
+    match output.is_empty() {
+        true => Ok("No differences.\n".into()),
+        false => Ok(output.join(if diffOptions.highlight { "\n" } else { "" }))
+    }
+}
+
+fn readMachines(path: &Path, options: &Options) -> Result<Vec<Machine>,String>
+{
+    let fileContent = readInputFile(path)?;
+    match parseMachines(&fileContent, options.nameDisplay) {
+        Ok(machines) => Ok(machines),
+        Err(e) => Err(format!("{}: {}", displayPath(path), e))
+    }
+}
+
+fn compareMachines<'a>(oldMachine: &'a Machine, newMachine: &'a Machine) -> MachineDiff<'a>
+{
+    let oldStates = listStates(oldMachine);
+    let newStates = listStates(newMachine);
+    let mut diff = MachineDiff{
+        name: newMachine.name.clone(),
+        addedStates: newStates.iter().copied().filter(|state| !oldStates.contains(state)).collect(),
+        removedStates: oldStates.iter().copied().filter(|state| !newStates.contains(state)).collect(),
+        addedRows: vec![],
+        removedRows: vec![],
+        changedRows: vec![]
+    };
+
+    let mut unmatchedOldRows: Vec<&Row> = oldMachine.rows.iter().collect();
+    for newRow in &newMachine.rows {
+        match unmatchedOldRows.iter().position(|oldRow| isSameTransition(oldRow, newRow)) {
+            Some(index) => {
+                let oldRow = unmatchedOldRows.remove(index);
+                if oldRow.guard != newRow.guard || oldRow.action != newRow.action {
+                    diff.changedRows.push((oldRow, newRow));
+                }
+            },
+            None => diff.addedRows.push(newRow)
+        }
+    }
+    diff.removedRows = unmatchedOldRows;
+    diff
+}
+
+fn isSameTransition(oldRow: &Row, newRow: &Row) -> bool
+{
+    oldRow.start == newRow.start && oldRow.event == newRow.event && oldRow.target == newRow.target
+}
+
+fn describeDiff(diff: &MachineDiff, options: &DiagramOptions) -> String
+{
+    let namePolicy = &options.namePolicy;
+    let mut lines = vec![format!("Machine {}:", diff.name)];
+    lines.extend(diff.addedStates.iter().map(|state| format!("  + state {}", namePolicy.apply(state))));
+    lines.extend(diff.removedStates.iter().map(|state| format!("  - state {}", namePolicy.apply(state))));
+    lines.extend(diff.addedRows.iter().map(|row| format!("  + transition {}", describeRow(row, options))));
+    lines.extend(diff.removedRows.iter().map(|row| format!("  - transition {}", describeRow(row, options))));
+    for (oldRow, newRow) in &diff.changedRows {
+        lines.push(format!("  ~ transition {}", describeRow(newRow, options)));
+        let (oldLabel, newLabel) = (TransitionLabel::new(oldRow, options), TransitionLabel::new(newRow, options));
+        if oldLabel.guard != newLabel.guard {
+            lines.push(format!("      guard: {} -> {}", describePart(oldLabel.guard), describePart(newLabel.guard)));
+        }
+        if oldLabel.actions != newLabel.actions {
+            let describeActions = |actions: Vec<String>| (!actions.is_empty()).then(|| actions.join("; "));
+            lines.push(format!("      action: {} -> {}",
+                describePart(describeActions(oldLabel.actions)), describePart(describeActions(newLabel.actions))));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Describes a row like `Start --> Target : on Event, if Guard, do Action`.
+pub(crate) fn describeRow(row: &Row, options: &DiagramOptions) -> String
+{
+    let mut description = format!("{} --> {}", options.namePolicy.apply(&row.start), options.namePolicy.apply(&row.target));
+    let labelLines = TransitionLabel::new(row, options).makeLines(ActionListLayout::Inline);
+    if !labelLines.is_empty() {
+        description += &format!(" : {}", labelLines.join(", "));
+    }
+    description
+}
+
+fn describePart(part: Option<String>) -> String
+{
+    part.unwrap_or_else(|| "(none)".into())
+}
+
+/// Renders the new version of the machine together with removed rows, colouring what was added, removed or changed.
+fn renderDiff(newMachine: &Machine, diff: &MachineDiff, options: &Options, diagramOptions: &DiagramOptions) -> String
+{
+    let mut rows = newMachine.rows.clone();
+    rows.extend(diff.removedRows.iter().map(|&row| row.clone()));
+    let mergedMachine = Machine{
+        name: newMachine.name.clone(),
+        qualifiedName: newMachine.qualifiedName.clone(),
+        namespace: newMachine.namespace.clone(),
+        rows,
+        initialStates: newMachine.initialStates.clone(),
+        declaresInitialState: newMachine.declaresInitialState,
+        stateLabels: newMachine.stateLabels.clone(),
+        stateBehaviors: newMachine.stateBehaviors.clone()
+    };
+
+    let mut diagram = buildDiagram(&mergedMachine, diagramOptions);
+    for transition in diagram.transitions.iter_mut().skip(newMachine.rows.len()) {
+        transition.color = Some(REMOVED_COLOR.into());
+    }
+    for (transition, row) in diagram.transitions.iter_mut().zip(&newMachine.rows) {
+        if diff.addedRows.iter().any(|added| std::ptr::eq(*added, row)) {
+            transition.color = Some(ADDED_COLOR.into());
+        } else if diff.changedRows.iter().any(|(_, changed)| std::ptr::eq(*changed, row)) {
+            transition.color = Some(CHANGED_COLOR.into());
+        }
+    }
+    diagram.colorStates(&diff.addedStates, ADDED_COLOR);
+    diagram.colorStates(&diff.removedStates, REMOVED_COLOR);
+    renderDiagram(&diagram, diagramOptions, options.format)
+}
@@ -0,0 +1,79 @@
+use crate::diagram::Diagram;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+
+pub(crate) fn renderDot(diagram: &Diagram) -> String
+{
+    let mut outputString = format!("digraph {} {{\n", quote(&diagram.name));
+    outputString.push_str("    node [shape=box, style=rounded];\n");
+    outputString.push_str("    \"[*]\" [shape=point, label=\"\"];\n");
+    for state in &diagram.states {
+        let mut attributes = vec![];
+        if state.label != state.id || !state.descriptions.is_empty() {
+            let lines: Vec<String> = [&state.label].into_iter().chain(&state.descriptions).map(|line| escape(line)).collect();
+            attributes.push(format!("label=\"{}\"", lines.join("\\n")));
+        }
+        if let Some(color) = &state.color {
+            attributes.push(format!("color={}, penwidth=2", quote(color)));
+        }
+        if !attributes.is_empty() {
+            outputString.push_str(&format!("    {} [{}];\n", quote(&state.id), attributes.join(", ")));
+        }
+    }
+    for initialState in &diagram.initialStates {
+        outputString.push_str(&format!("    \"[*]\" -> {};\n", quote(initialState)));
+    }
+    for transition in &diagram.transitions {
+        outputString.push_str(&format!("    {} -> {}", quote(&transition.start), quote(&transition.target)));
+        let mut attributes = vec![];
+        let label: Vec<String> = transition.labelLines.iter().map(String::as_str)
+            .chain(transition.note.iter().flat_map(|note| note.lines()))
+            .map(escape)
+            .collect();
+        if !label.is_empty() {
+            attributes.push(format!("label=\"{}\"", label.join("\\n")));
+        }
+        if let Some(color) = &transition.color {
+            attributes.push(format!("color={}, fontcolor={}", quote(color), quote(color)));
+        }
+        if !attributes.is_empty() {
+            outputString.push_str(&format!(" [{}]", attributes.join(", ")));
+        }
+        outputString.push_str(";\n");
+    }
+    outputString.push('}');
+    outputString
+}
+
+fn quote(name: &str) -> String
+{
+    format!("\"{}\"", escape(name))
+}
+
+fn escape(text: &str) -> String
+{
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Lays out a DOT graph as SVG with the `dot` program from Graphviz, which has to be installed.
+pub(crate) fn convertDotToSvg(dotText: &str) -> Result<String,String>
+{
+    let process = Command::new("dot").arg("-Tsvg").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn();
+    let mut process = match process {
+        Ok(process) => process,
+        Err(e) => return Err(format!("Failed to run dot from Graphviz, error: {}", e))
+    };
+    if let Err(e) = process.stdin.take().unwrap().write_all(dotText.as_bytes()) {
+        return Err(format!("Failed to write to dot, error: {}", e));
+    }
+    let output = match process.wait_with_output() {
+        Ok(output) => output,
+        Err(e) => return Err(format!("Failed to run dot from Graphviz, error: {}", e))
+    };
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(format!("Command dot failed, error: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
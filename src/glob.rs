@@ -0,0 +1,70 @@
+use regex::Regex;
+use std::path::Path;
+
+
+pub(crate) struct Glob
+{
+    regex: Regex,
+    matchesFileNameOnly: bool
+}
+
+impl Glob
+{
+    pub(crate) fn new(pattern: &str) -> Result<Self,String>
+    {
+        let regex = match Regex::new(&makeRegexPattern(pattern)) {
+            Ok(regex) => regex,
+            Err(e) => return Err(format!("Invalid glob pattern: {}, error: {}", pattern, e))
+        };
+        Ok(Self{regex, matchesFileNameOnly: !pattern.contains('/')})
+    }
+
+    pub(crate) fn isMatch(&self, relativePath: &Path) -> bool
+    {
+        let text = match self.matchesFileNameOnly {
+            true => match relativePath.file_name() {
+                Some(fileName) => fileName.to_string_lossy().into_owned(),
+                None => return false
+            },
+            false => relativePath.to_string_lossy().replace('\\', "/")
+        };
+        self.regex.is_match(&text)
+    }
+}
+
+fn makeRegexPattern(pattern: &str) -> String
+{
+    let mut output = String::from("^");
+    let mut characters = pattern.chars().peekable();
+    let mut braceDepth = 0;
+    while let Some(character) = characters.next() {
+        match character {
+            '*' => {
+                if characters.peek() == Some(&'*') {
+                    characters.next();
+                    if characters.peek() == Some(&'/') {
+                        characters.next();
+                        output.push_str("(?:.*/)?");
+                    } else {
+                        output.push_str(".*");
+                    }
+                } else {
+                    output.push_str("[^/]*");
+                }
+            },
+            '?' => output.push_str("[^/]"),
+            '{' => {
+                braceDepth += 1;
+                output.push_str("(?:");
+            },
+            '}' if braceDepth > 0 => {
+                braceDepth -= 1;
+                output.push(')');
+            },
+            ',' if braceDepth > 0 => output.push('|'),
+            _ => output.push_str(&regex::escape(&character.to_string()))
+        }
+    }
+    output.push('$');
+    output
+}
@@ -0,0 +1,129 @@
+use crate::type_expression::TypeExpression;
+
+
+/// A guard with the boolean functor combinators from `boost::msm::front::euml` recognized.
+#[derive(PartialEq)]
+pub(crate) enum GuardExpression
+{
+    And(Vec<GuardExpression>),
+    Or(Vec<GuardExpression>),
+    Not(Box<GuardExpression>),
+    Functor(TypeExpression)
+}
+
+impl GuardExpression
+{
+    pub(crate) fn new(guard: &TypeExpression) -> Self
+    {
+        let (name, arguments) = match guard {
+            TypeExpression::Template{name, arguments} => (name, arguments),
+            _ => return Self::Functor(guard.clone())
+        };
+        if !isCombinator(name, arguments.len()) {
+            return Self::Functor(guard.clone());
+        }
+        match findCombinator(name) {
+            Some(Combinator::And) => Self::And(arguments.iter().map(Self::new).collect()),
+            Some(Combinator::Or) => Self::Or(arguments.iter().map(Self::new).collect()),
+            _ => Self::Not(Box::new(Self::new(&arguments[0])))
+        }
+    }
+
+    /// Tells whether one of the guards is the negation of the other, so that they can never hold together.
+    pub(crate) fn excludes(&self, other: &GuardExpression) -> bool
+    {
+        match (self, other) {
+            (Self::Not(operand), _) if **operand == *other => true,
+            (_, Self::Not(operand)) if **operand == *self => true,
+            _ => false
+        }
+    }
+
+    /// Evaluates the guard from left to right with short-circuiting, deciding about each functor with `decide`.
+    /// Returns `None` when `decide` does.
+    pub(crate) fn evaluate(&self, decide: &mut impl FnMut(&TypeExpression) -> Option<bool>) -> Option<bool>
+    {
+        match self {
+            Self::And(operands) => {
+                for operand in operands {
+                    if !operand.evaluate(decide)? {
+                        return Some(false);
+                    }
+                }
+                Some(true)
+            },
+            Self::Or(operands) => {
+                for operand in operands {
+                    if operand.evaluate(decide)? {
+                        return Some(true);
+                    }
+                }
+                Some(false)
+            },
+            Self::Not(operand) => operand.evaluate(decide).map(|value| !value),
+            Self::Functor(functor) => decide(functor)
+        }
+    }
+
+    /// Renders the guard in infix notation, e.g. `!IsEmpty && (A || B)`, formatting functors with `formatFunctor`.
+    pub(crate) fn render(&self, formatFunctor: &impl Fn(&TypeExpression) -> String) -> String
+    {
+        match self {
+            Self::And(operands) => self.renderOperands(operands, " && ", formatFunctor),
+            Self::Or(operands) => self.renderOperands(operands, " || ", formatFunctor),
+            Self::Not(operand) => format!("!{}", operand.renderAsOperand(self, formatFunctor)),
+            Self::Functor(functor) => formatFunctor(functor)
+        }
+    }
+
+    fn renderOperands(&self, operands: &[GuardExpression], separator: &str, formatFunctor: &impl Fn(&TypeExpression) -> String)
+        -> String
+    {
+        let operands: Vec<String> = operands.iter().map(|operand| operand.renderAsOperand(self, formatFunctor)).collect();
+        operands.join(separator)
+    }
+
+    fn renderAsOperand(&self, parent: &GuardExpression, formatFunctor: &impl Fn(&TypeExpression) -> String) -> String
+    {
+        match self.precedence() < parent.precedence() {
+            true => format!("({})", self.render(formatFunctor)),
+            false => self.render(formatFunctor)
+        }
+    }
+
+    fn precedence(&self) -> u8
+    {
+        match self {
+            Self::Or(_) => 1,
+            Self::And(_) => 2,
+            Self::Not(_) => 3,
+            Self::Functor(_) => 4
+        }
+    }
+}
+
+enum Combinator
+{
+    And,
+    Or,
+    Not
+}
+
+fn findCombinator(name: &str) -> Option<Combinator>
+{
+    match name.trim_start_matches("::") {
+        "And_" | "euml::And_" | "front::euml::And_" | "msm::front::euml::And_" | "boost::msm::front::euml::And_" =>
+            Some(Combinator::And),
+        "Or_" | "euml::Or_" | "front::euml::Or_" | "msm::front::euml::Or_" | "boost::msm::front::euml::Or_" =>
+            Some(Combinator::Or),
+        "Not_" | "euml::Not_" | "front::euml::Not_" | "msm::front::euml::Not_" | "boost::msm::front::euml::Not_" =>
+            Some(Combinator::Not),
+        _ => None
+    }
+}
+
+/// Tells whether a template with the given name and number of arguments is one of the boolean functor combinators.
+pub(crate) fn isCombinator(name: &str, argumentCount: usize) -> bool
+{
+    matches!((findCombinator(name), argumentCount), (Some(Combinator::And | Combinator::Or), 2..) | (Some(Combinator::Not), 1))
+}
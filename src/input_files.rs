@@ -0,0 +1,101 @@
+use crate::cli::isStdin;
+use crate::glob::Glob;
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+
+const DEFAULT_INCLUDE_PATTERNS: [&str; 1] = ["*.{cpp,cxx,cc,c++,hpp,hxx,hh,h,ipp,inl}"];
+
+pub(crate) struct InputFile
+{
+    pub(crate) path: PathBuf,
+    pub(crate) wasFoundInDirectory: bool
+}
+
+/// Patterns selecting files analyzed from input directories, matched against paths relative to the directories.
+pub(crate) struct FileFilter
+{
+    includes: Vec<Glob>,
+    excludes: Vec<Glob>
+}
+
+impl FileFilter
+{
+    pub(crate) fn new(includePatterns: &[String], excludePatterns: &[String]) -> Result<Self,String>
+    {
+        let includes = match includePatterns.is_empty() {
+            true => makeGlobs(&DEFAULT_INCLUDE_PATTERNS)?,
+            false => makeGlobs(includePatterns)?
+        };
+        Ok(Self{includes, excludes: makeGlobs(excludePatterns)?})
+    }
+
+    pub(crate) fn isMatch(&self, relativePath: &Path) -> bool
+    {
+        self.includes.iter().any(|glob| glob.isMatch(relativePath)) && !self.excludes.iter().any(|glob| glob.isMatch(relativePath))
+    }
+}
+
+pub(crate) fn collectInputFiles(paths: &[PathBuf], includePatterns: &[String], excludePatterns: &[String])
+    -> Result<Vec<InputFile>,String>
+{
+    let filter = FileFilter::new(includePatterns, excludePatterns)?;
+    let mut inputFiles = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let mut filesInDirectory = vec![];
+            collectFilesInDirectory(path, &mut filesInDirectory)?;
+            filesInDirectory.sort();
+            for file in filesInDirectory {
+                if filter.isMatch(file.strip_prefix(path).unwrap_or(&file)) {
+                    inputFiles.push(InputFile{path: file, wasFoundInDirectory: true});
+                }
+            }
+        } else {
+            inputFiles.push(InputFile{path: path.clone(), wasFoundInDirectory: false});
+        }
+    }
+    Ok(inputFiles)
+}
+
+fn makeGlobs<T: AsRef<str>>(patterns: &[T]) -> Result<Vec<Glob>,String>
+{
+    patterns.iter().map(|pattern| Glob::new(pattern.as_ref())).collect()
+}
+
+/// Collects files recursively, skipping symbolic links to directories, so that links to parents do not loop endlessly.
+fn collectFilesInDirectory(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(),String>
+{
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) => return Err(format!("Failed to read directory: {:?}, error: {}", directory, e))
+    };
+    for entry in entries {
+        let (path, fileType) = match entry.and_then(|entry| Ok((entry.path(), entry.file_type()?))) {
+            Ok(found) => found,
+            Err(e) => return Err(format!("Failed to read directory: {:?}, error: {}", directory, e))
+        };
+        if fileType.is_dir() {
+            collectFilesInDirectory(&path, files)?;
+        } else if !(fileType.is_symlink() && path.is_dir()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn readInputFile(path: &Path) -> Result<String,String>
+{
+    if isStdin(path) {
+        let mut content = String::new();
+        return match std::io::stdin().read_to_string(&mut content) {
+            Ok(_) => Ok(content),
+            Err(e) => Err(format!("Failed to read standard input, error: {}", e))
+        };
+    }
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) => Err(format!("Failed to read file: {:?}, error: {}", path, e))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::transition_label::TransitionLabel;
+
+
+const PART_NAMES: [&str; 3] = ["event", "guard", "action"];
+
+/// A format of transition labels like `{event}{ [guard]}{ / action}`, where each section in braces names a part of the
+/// transition and is left out together with its surrounding text when the transition has no such part. A `\n` starts
+/// a new line.
+#[derive(Clone, Debug)]
+pub(crate) struct LabelTemplate
+{
+    segments: Vec<Segment>
+}
+
+#[derive(Clone, Debug)]
+enum Segment
+{
+    Text(String),
+    Section{prefix: String, part: LabelPart, suffix: String}
+}
+
+#[derive(Clone, Copy, Debug)]
+enum LabelPart
+{
+    Event,
+    Guard,
+    Action
+}
+
+impl LabelTemplate
+{
+    pub(crate) fn parse(template: &str) -> Result<Self,String>
+    {
+        let template = template.replace("\\n", "\n");
+        let mut segments = vec![];
+        let mut rest = template.as_str();
+        while let Some(sectionStart) = rest.find('{') {
+            if sectionStart > 0 {
+                segments.push(Segment::Text(rest[..sectionStart].into()));
+            }
+            let sectionLength = match rest[sectionStart..].find('}') {
+                Some(length) => length,
+                None => return Err(format!("Label template has an unclosed section: {}", &rest[sectionStart..]))
+            };
+            segments.push(parseSection(&rest[sectionStart + 1..sectionStart + sectionLength])?);
+            rest = &rest[sectionStart + sectionLength + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.into()));
+        }
+        Ok(Self{segments})
+    }
+
+    /// Renders the label, trimming lines, so that spaces of sections are not left at their ends, and skipping empty ones.
+    pub(crate) fn render(&self, label: &TransitionLabel) -> Vec<String>
+    {
+        let mut text = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(segmentText) => text += segmentText,
+                Segment::Section{prefix, part, suffix} => {
+                    let value = match part {
+                        LabelPart::Event => label.event.clone(),
+                        LabelPart::Guard => label.guard.clone(),
+                        LabelPart::Action => (!label.actions.is_empty()).then(|| label.actions.join("; "))
+                    };
+                    if let Some(value) = value {
+                        text += &format!("{}{}{}", prefix, value, suffix);
+                    }
+                }
+            }
+        }
+        text.split('\n').map(|line| line.trim()).filter(|line| !line.is_empty()).map(|line| line.into()).collect()
+    }
+}
+
+fn parseSection(section: &str) -> Result<Segment,String>
+{
+    let (index, partName) = match PART_NAMES.iter().filter_map(|name| section.find(name).map(|index| (index, *name))).min() {
+        Some(found) => found,
+        None => return Err(format!("Section {{{}}} of the label template should contain one of: {}.", section, PART_NAMES.join(", ")))
+    };
+    let part = match partName {
+        "event" => LabelPart::Event,
+        "guard" => LabelPart::Guard,
+        _ => LabelPart::Action
+    };
+    Ok(Segment::Section{prefix: section[..index].into(), part, suffix: section[index + partName.len()..].into()})
+}
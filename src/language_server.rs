@@ -0,0 +1,508 @@
+use crate::analysis::{Severity, analyzeMachine, listStates, unqualifiedName};
+use crate::cli::Options;
+use crate::diagram::{DiagramOptions, buildDiagram};
+use crate::diff::describeRow;
+use crate::machine::ParsedText;
+use crate::name_resolver::NameDisplay;
+use crate::renderer::{OutputFormat, renderDiagram};
+use crate::row::Row;
+use crate::svg_renderer::{SvgRenderer, renderSvg};
+
+use regex::Regex;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::ops::Range;
+
+
+const SERVER_NAME: &str = "msm-grapher";
+const RENDER_METHOD: &str = "msm-grapher/render";
+const SVG_FORMAT_NAME: &str = "svg";
+const INCREMENTAL_SYNC: i64 = 2;
+const ERROR_SEVERITY: i64 = 1;
+const WARNING_SEVERITY: i64 = 2;
+const PARSE_ERROR_CODE: i64 = -32700;
+const INVALID_REQUEST_CODE: i64 = -32600;
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+const INVALID_PARAMS_CODE: i64 = -32602;
+const REQUEST_FAILED_CODE: i64 = -32803;
+/// Size of the largest message accepted, far above the size of any source file, so that a broken header does not
+/// exhaust memory.
+const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Serves the Language Server Protocol on the standard input and output, until the client sends the exit notification.
+pub(crate) fn runLanguageServer(options: &Options) -> Result<(),String>
+{
+    let diagramOptions = options.makeDiagramOptions()?;
+    let server = LanguageServer::new(options, &diagramOptions, std::io::stdin().lock(), std::io::stdout().lock());
+    server.run()
+}
+
+/// An open document with the transition tables parsed from its current text.
+struct Document
+{
+    text: String,
+    parsedText: ParsedText
+}
+
+impl Document
+{
+    fn new(text: String, nameDisplay: NameDisplay) -> Self
+    {
+        let parsedText = ParsedText::parse(&text, nameDisplay);
+        Self{text, parsedText}
+    }
+}
+
+/// An error sent back in the response to a request.
+struct ResponseError
+{
+    code: i64,
+    message: String
+}
+
+impl ResponseError
+{
+    fn new(code: i64, message: impl Into<String>) -> Self
+    {
+        Self{code, message: message.into()}
+    }
+}
+
+struct LanguageServer<'a, R: BufRead, W: Write>
+{
+    options: &'a Options,
+    diagramOptions: &'a DiagramOptions,
+    input: R,
+    output: W,
+    documents: HashMap<String, Document>,
+    isShutDown: bool
+}
+
+impl<'a, R: BufRead, W: Write> LanguageServer<'a, R, W>
+{
+    fn new(options: &'a Options, diagramOptions: &'a DiagramOptions, input: R, output: W) -> Self
+    {
+        Self{options, diagramOptions, input, output, documents: HashMap::new(), isShutDown: false}
+    }
+
+    fn run(mut self) -> Result<(),String>
+    {
+        while let Some(content) = self.readMessage()? {
+            let message: Value = match serde_json::from_str(&content) {
+                Ok(message) => message,
+                Err(e) => {
+                    self.sendError(Value::Null, ResponseError::new(PARSE_ERROR_CODE, format!("Invalid JSON: {}", e)))?;
+                    continue;
+                }
+            };
+            let method = message["method"].as_str().unwrap_or_default().to_string();
+            let params = &message["params"];
+            let id = match message.get("id") {
+                Some(id) => id.clone(),
+                None => {
+                    if method == "exit" {
+                        return match self.isShutDown {
+                            true => Ok(()),
+                            false => Err("Language client exited without requesting a shutdown.".into())
+                        };
+                    }
+                    self.handleNotification(&method, params)?;
+                    continue;
+                }
+            };
+            match self.handleRequest(&method, params) {
+                Ok(result) => self.send(json!({"jsonrpc": "2.0", "id": id, "result": result}))?,
+                Err(error) => self.sendError(id, error)?
+            }
+        }
+        Ok(())
+    }
+
+    fn handleRequest(&mut self, method: &str, params: &Value) -> Result<Value,ResponseError>
+    {
+        if self.isShutDown {
+            return Err(ResponseError::new(INVALID_REQUEST_CODE, "The server was shut down."));
+        }
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": {"openClose": true, "change": INCREMENTAL_SYNC},
+                    "hoverProvider": true,
+                    "definitionProvider": true
+                },
+                "serverInfo": {"name": SERVER_NAME, "version": env!("CARGO_PKG_VERSION")}
+            })),
+            "shutdown" => {
+                self.isShutDown = true;
+                Ok(Value::Null)
+            },
+            "textDocument/hover" => self.describeStateAt(params),
+            "textDocument/definition" => self.findDefinitionAt(params),
+            RENDER_METHOD => self.renderDocument(params),
+            _ => Err(ResponseError::new(METHOD_NOT_FOUND_CODE, format!("Unknown method: {}", method)))
+        }
+    }
+
+    /// Keeps texts of open documents up to date and publishes diagnostics after every change. Unknown notifications are
+    /// ignored, as the protocol requires.
+    fn handleNotification(&mut self, method: &str, params: &Value) -> Result<(),String>
+    {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default().to_string();
+                self.updateDocument(&uri, Document::new(text, self.options.nameDisplay))
+            },
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array().map_or(&[][..], |changes| changes.as_slice());
+                let document = match self.documents.remove(&uri) {
+                    Some(document) => document,
+                    None => Document::new(String::new(), self.options.nameDisplay)
+                };
+                let document = self.applyChanges(document, changes);
+                self.updateDocument(&uri, document)
+            },
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                self.publishDiagnostics(&uri, vec![])
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Applies incremental changes, parsing again only the transition tables they were made in. When a change may affect
+    /// more than its table, or replaces the whole text, the whole text is parsed again after applying all of them.
+    fn applyChanges(&self, document: Document, changes: &[Value]) -> Document
+    {
+        let Document{mut text, mut parsedText} = document;
+        let mut isParsed = true;
+        for change in changes {
+            let edit = applyChange(&mut text, change);
+            isParsed = isParsed && match edit {
+                Some((inserted, removedText)) =>
+                    parsedText.reparseChangedTable(&text, inserted, &removedText, self.options.nameDisplay),
+                None => false
+            };
+        }
+        if !isParsed {
+            parsedText = ParsedText::parse(&text, self.options.nameDisplay);
+        }
+        Document{text, parsedText}
+    }
+
+    fn updateDocument(&mut self, uri: &str, document: Document) -> Result<(),String>
+    {
+        let diagnostics = makeDiagnostics(uri, &document);
+        self.documents.insert(uri.into(), document);
+        self.publishDiagnostics(uri, diagnostics)
+    }
+
+    fn publishDiagnostics(&mut self, uri: &str, diagnostics: Vec<Value>) -> Result<(),String>
+    {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics}
+        }))
+    }
+
+    /// Lists transitions entering and leaving the state named at the position, in every machine of the document.
+    fn describeStateAt(&self, params: &Value) -> Result<Value,ResponseError>
+    {
+        let (document, word) = match self.findWordAt(params)? {
+            Some(found) => found,
+            None => return Ok(Value::Null)
+        };
+        let machines = match document.parsedText.machines() {
+            Ok(machines) => machines,
+            Err(_) => return Ok(Value::Null)
+        };
+        let mut sections = vec![];
+        for machine in machines {
+            let state = match listStates(machine).into_iter().find(|state| self.isNaming(state, &word.text)) {
+                Some(state) => state,
+                None => continue
+            };
+            let describeRows = |isIncluded: &dyn Fn(&Row) -> bool| -> String {
+                let lines: Vec<String> = machine.rows.iter().filter(|row| isIncluded(row))
+                    .map(|row| format!("- `{}`", describeRow(row, self.diagramOptions)))
+                    .collect();
+                match lines.is_empty() {
+                    true => "- none".into(),
+                    false => lines.join("\n")
+                }
+            };
+            sections.push(format!("State **{}** in {}\n\nIncoming:\n{}\n\nOutgoing:\n{}",
+                self.diagramOptions.namePolicy.apply(state), machine.name,
+                describeRows(&|row| row.target == state), describeRows(&|row| row.start == state)));
+        }
+        match sections.is_empty() {
+            true => Ok(Value::Null),
+            false => Ok(json!({
+                "contents": {"kind": "markdown", "value": sections.join("\n\n---\n\n")},
+                "range": word.range
+            }))
+        }
+    }
+
+    /// Finds the struct or class of the state or event named at the position, when the document defines it.
+    fn findDefinitionAt(&self, params: &Value) -> Result<Value,ResponseError>
+    {
+        let (document, word) = match self.findWordAt(params)? {
+            Some(found) => found,
+            None => return Ok(Value::Null)
+        };
+        let machines = match document.parsedText.machines() {
+            Ok(machines) => machines,
+            Err(_) => return Ok(Value::Null)
+        };
+        let isUsedInRows = machines.iter().flat_map(|machine| &machine.rows)
+            .any(|row| [&row.start, &row.event, &row.target].into_iter().any(|name| self.isNaming(name, &word.text)));
+        if !isUsedInRows {
+            return Ok(Value::Null);
+        }
+        let name = regex::escape(unqualifiedName(&word.text));
+        let definitionRegex = Regex::new(&format!(r"\b(?:struct|class)\s+({})\b[^;{{}}()]*\{{", name)).unwrap();
+        match definitionRegex.captures(&document.text).and_then(|captures| captures.get(1)) {
+            Some(found) => Ok(json!({
+                "uri": params["textDocument"]["uri"],
+                "range": makeRange(&document.text, found.start(), found.end())
+            })),
+            None => Ok(Value::Null)
+        }
+    }
+
+    /// Renders diagrams of all machines in the document, in the format given in the parameters or the one from options,
+    /// or as SVG laid out by the built-in renderer, which does not need Graphviz.
+    fn renderDocument(&self, params: &Value) -> Result<Value,ResponseError>
+    {
+        let document = self.findDocument(params)?;
+        let machines = match document.parsedText.machines() {
+            Ok(machines) => machines,
+            Err(e) => return Err(ResponseError::new(REQUEST_FAILED_CODE, format!("line {}: {}", e.line, e.message)))
+        };
+        let formatName = params["format"].as_str();
+        let format = match formatName {
+            Some(SVG_FORMAT_NAME) => None,
+            Some(name) => Some(OutputFormat::fromName(name).map_err(|e| ResponseError::new(INVALID_PARAMS_CODE, e))?),
+            None => Some(self.options.format)
+        };
+        let mut diagrams = vec![];
+        for (index, machine) in machines.iter().enumerate() {
+            let diagram = buildDiagram(machine, self.diagramOptions);
+            let text = match format {
+                Some(format) => renderDiagram(&diagram, self.diagramOptions, format),
+                None => renderSvg(&diagram, SvgRenderer::BuiltIn, &format!("machine-{}", index))
+                    .map_err(|e| ResponseError::new(REQUEST_FAILED_CODE, e))?
+            };
+            diagrams.push(json!({"machine": machine.name, "text": text}));
+        }
+        Ok(json!({"diagrams": diagrams}))
+    }
+
+    fn findDocument(&self, params: &Value) -> Result<&Document,ResponseError>
+    {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match self.documents.get(uri) {
+            Some(document) => Ok(document),
+            None => Err(ResponseError::new(INVALID_PARAMS_CODE, format!("Document is not open: {}", uri)))
+        }
+    }
+
+    fn findWordAt(&self, params: &Value) -> Result<Option<(&Document, Word)>,ResponseError>
+    {
+        let document = self.findDocument(params)?;
+        let line = params["position"]["line"].as_u64().unwrap_or_default() as usize;
+        let character = params["position"]["character"].as_u64().unwrap_or_default() as usize;
+        Ok(findWord(&document.text, findOffset(&document.text, line, character)).map(|word| (document, word)))
+    }
+
+    /// Tells whether a name written in the code refers to the state or event, also without namespaces or templates.
+    fn isNaming(&self, name: &str, writtenName: &str) -> bool
+    {
+        self.diagramOptions.namePolicy.isMatch(name, writtenName) || unqualifiedName(name) == unqualifiedName(writtenName)
+    }
+
+    fn sendError(&mut self, id: Value, error: ResponseError) -> Result<(),String>
+    {
+        self.send(json!({"jsonrpc": "2.0", "id": id, "error": {"code": error.code, "message": error.message}}))
+    }
+
+    fn send(&mut self, message: Value) -> Result<(),String>
+    {
+        let content = message.to_string();
+        match write!(self.output, "Content-Length: {}\r\n\r\n{}", content.len(), content).and_then(|_| self.output.flush()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("Failed to write output, error: {}", e))
+        }
+    }
+
+    /// Reads the content of the next message framed with a `Content-Length` header, returning `None` when the input
+    /// ended.
+    fn readMessage(&mut self) -> Result<Option<String>,String>
+    {
+        let mut contentLength = None;
+        loop {
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) => return Ok(None),
+                Ok(_) => (),
+                Err(e) => return Err(format!("Failed to read input, error: {}", e))
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') && name.eq_ignore_ascii_case("Content-Length") {
+                match value.trim().parse::<usize>() {
+                    Ok(length) => contentLength = Some(length),
+                    Err(_) => return Err(format!("Invalid Content-Length header: {}", line))
+                }
+            }
+        }
+        let contentLength = match contentLength {
+            Some(length) => length,
+            None => return Err("Message has no Content-Length header.".into())
+        };
+        if contentLength > MAX_CONTENT_LENGTH {
+            return Err(format!("Message is longer than the limit of {} bytes: {}", MAX_CONTENT_LENGTH, contentLength));
+        }
+        let mut content = vec![0; contentLength];
+        if let Err(e) = self.input.read_exact(&mut content) {
+            return Err(format!("Failed to read input, error: {}", e));
+        }
+        Ok(Some(String::from_utf8_lossy(&content).into_owned()))
+    }
+}
+
+/// Reports syntax errors of transition tables, and the findings of the analyze command in the tables which were parsed.
+fn makeDiagnostics(uri: &str, document: &Document) -> Vec<Value>
+{
+    let text = &document.text;
+    let mut diagnostics = vec![];
+    for table in &document.parsedText.tables {
+        let machine = match &table.machine {
+            Ok(machine) => machine,
+            Err(e) => {
+                diagnostics.push(json!({
+                    "range": makeRange(text, e.span.start, e.span.end),
+                    "severity": ERROR_SEVERITY,
+                    "source": SERVER_NAME,
+                    "message": e.message
+                }));
+                continue;
+            }
+        };
+        for finding in analyzeMachine(machine, text) {
+            let relatedInformation: Vec<Value> = finding.notes.iter()
+                .map(|note| json!({"location": {"uri": uri, "range": makeLineRange(text, note.line)}, "message": note.message}))
+                .collect();
+            diagnostics.push(json!({
+                "range": makeLineRange(text, finding.line),
+                "severity": match finding.severity {
+                    Severity::Error => ERROR_SEVERITY,
+                    Severity::Warning => WARNING_SEVERITY
+                },
+                "source": SERVER_NAME,
+                "message": finding.message,
+                "relatedInformation": relatedInformation
+            }));
+        }
+    }
+    diagnostics
+}
+
+/// A name written in a document, which can be qualified with namespaces, and its range.
+struct Word
+{
+    text: String,
+    range: Value
+}
+
+fn findWord(text: &str, offset: usize) -> Option<Word>
+{
+    let isWordByte = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b':';
+    let bytes = text.as_bytes();
+    let mut start = offset;
+    while start > 0 && isWordByte(bytes[start - 1]) {
+        start -= 1;
+    }
+    let mut end = offset;
+    while end < bytes.len() && isWordByte(bytes[end]) {
+        end += 1;
+    }
+    while start < end && bytes[start] == b':' {
+        start += 1;
+    }
+    while end > start && bytes[end - 1] == b':' {
+        end -= 1;
+    }
+    match start < end {
+        true => Some(Word{text: text[start..end].into(), range: makeRange(text, start, end)}),
+        false => None
+    }
+}
+
+/// Replaces the range of the change, or the whole text when the change has no range. Returns the range of the inserted
+/// text and the removed text, or `None` when the whole text was replaced.
+fn applyChange(text: &mut String, change: &Value) -> Option<(Range<usize>, String)>
+{
+    let newText = change["text"].as_str().unwrap_or_default();
+    let range = &change["range"];
+    if range.is_null() {
+        *text = newText.into();
+        return None;
+    }
+    let findPositionOffset = |position: &Value| findOffset(text, position["line"].as_u64().unwrap_or_default() as usize,
+        position["character"].as_u64().unwrap_or_default() as usize);
+    let start = findPositionOffset(&range["start"]);
+    let end = findPositionOffset(&range["end"]).max(start);
+    let removedText = text[start..end].to_string();
+    text.replace_range(start..end, newText);
+    Some((start..start + newText.len(), removedText))
+}
+
+/// Converts a position given as a line counted from 0 and a character counted in UTF-16 code units, as in the protocol,
+/// into a byte offset. Positions past the end of a line are moved to its end.
+fn findOffset(text: &str, line: usize, character: usize) -> usize
+{
+    let lineStart = match line {
+        0 => 0,
+        _ => match text.match_indices('\n').nth(line - 1) {
+            Some((index, _)) => index + 1,
+            None => return text.len()
+        }
+    };
+    let lineText = text[lineStart..].split('\n').next().unwrap_or_default().trim_end_matches('\r');
+    let mut units = 0;
+    for (index, c) in lineText.char_indices() {
+        if units >= character {
+            return lineStart + index;
+        }
+        units += c.len_utf16();
+    }
+    lineStart + lineText.len()
+}
+
+/// Converts a byte offset into a position of the protocol.
+fn makePosition(text: &str, offset: usize) -> Value
+{
+    let before = &text[..offset];
+    let lineStart = before.rfind('\n').map_or(0, |index| index + 1);
+    json!({"line": before.matches('\n').count(), "character": before[lineStart..].encode_utf16().count()})
+}
+
+fn makeRange(text: &str, start: usize, end: usize) -> Value
+{
+    json!({"start": makePosition(text, start), "end": makePosition(text, end)})
+}
+
+/// Makes the range of a line counted from 1, as in findings, without its indentation.
+fn makeLineRange(text: &str, line: usize) -> Value
+{
+    let lineStart = findOffset(text, line.saturating_sub(1), 0);
+    let lineText = text[lineStart..].split('\n').next().unwrap_or_default().trim_end();
+    let indentation = lineText.len() - lineText.trim_start().len();
+    makeRange(text, lineStart + indentation, lineStart + lineText.len())
+}
@@ -0,0 +1,278 @@
+use crate::action_sequence::flattenActions;
+use crate::analysis::{Finding, FindingNote, Report, Severity, checkInputFiles, listStates, makeFinding, unqualifiedName};
+use crate::cli::Options;
+use crate::machine::Machine;
+use crate::row::Row;
+use crate::transition_label::shouldBeShown;
+
+use regex::Regex;
+use std::collections::{HashSet, VecDeque};
+
+
+pub(crate) const DEFAULT_NAMING_PATTERN: &str = "^[A-Z][A-Za-z0-9]*$";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LintRule
+{
+    Naming,
+    UnsortedRows,
+    DuplicateRow,
+    SelfLoopWithoutAction,
+    UnguardedNoneEvent,
+    MissingInitialState
+}
+
+const LINT_RULES: [LintRule; 6] = [
+    LintRule::Naming,
+    LintRule::UnsortedRows,
+    LintRule::DuplicateRow,
+    LintRule::SelfLoopWithoutAction,
+    LintRule::UnguardedNoneEvent,
+    LintRule::MissingInitialState
+];
+
+impl LintRule
+{
+    pub(crate) fn fromId(id: &str) -> Result<Self,String>
+    {
+        match LINT_RULES.into_iter().find(|rule| rule.id() == id) {
+            Some(rule) => Ok(rule),
+            None => Err(format!("Unknown lint rule: {}, expected one of: {}.",
+                id, LINT_RULES.map(|rule| rule.id()).join(", ")))
+        }
+    }
+
+    pub(crate) fn id(self) -> &'static str
+    {
+        match self {
+            Self::Naming => "naming",
+            Self::UnsortedRows => "unsorted-rows",
+            Self::DuplicateRow => "duplicate-row",
+            Self::SelfLoopWithoutAction => "self-loop-without-action",
+            Self::UnguardedNoneEvent => "unguarded-none-event",
+            Self::MissingInitialState => "missing-initial-state"
+        }
+    }
+}
+
+/// Settings of the lint command. A rule set to `None` is disabled, rules not mentioned have the warning severity.
+pub(crate) struct LintOptions
+{
+    pub(crate) severities: Vec<(LintRule, Option<Severity>)>,
+    pub(crate) stateNaming: String,
+    pub(crate) eventNaming: String
+}
+
+impl LintOptions
+{
+    pub(crate) fn new() -> Self
+    {
+        Self{severities: vec![], stateNaming: DEFAULT_NAMING_PATTERN.into(), eventNaming: DEFAULT_NAMING_PATTERN.into()}
+    }
+
+    fn severityOf(&self, rule: LintRule) -> Option<Severity>
+    {
+        match self.severities.iter().rfind(|(configuredRule, _)| *configuredRule == rule) {
+            Some((_, severity)) => *severity,
+            None => Some(Severity::Warning)
+        }
+    }
+}
+
+/// Parses a `RULE=SEVERITY` setting, where the severity is one of: off, warning, error.
+pub(crate) fn parseRuleSetting(value: &str) -> Result<(LintRule, Option<Severity>),String>
+{
+    let (id, severity) = match value.split_once('=') {
+        Some(parts) => parts,
+        None => return Err(format!("Option --rule requires a value like RULE=SEVERITY, got: {}.", value))
+    };
+    let severity = match severity {
+        "off" => None,
+        "warning" => Some(Severity::Warning),
+        "error" => Some(Severity::Error),
+        _ => return Err(format!("Unknown rule severity: {}, expected one of: off, warning, error.", severity))
+    };
+    Ok((LintRule::fromId(id)?, severity))
+}
+
+/// Checks every machine found in the input files against the enabled rules and returns a report listing the findings.
+pub(crate) fn lintInputFiles(options: &Options, lintOptions: &LintOptions) -> Result<Report,String>
+{
+    let linter = Linter::new(lintOptions)?;
+    checkInputFiles(options, |machine, _| linter.lintMachine(machine))
+}
+
+fn compileNamingPattern(pattern: &str) -> Result<Regex,String>
+{
+    match Regex::new(pattern) {
+        Ok(regex) => Ok(regex),
+        Err(e) => Err(format!("Invalid naming pattern: {}, error: {}", pattern, e))
+    }
+}
+
+/// Checks machines against the rules enabled in the lint options.
+pub(crate) struct Linter<'a>
+{
+    options: &'a LintOptions,
+    stateNaming: Regex,
+    eventNaming: Regex,
+    allowRegex: Regex
+}
+
+impl<'a> Linter<'a>
+{
+    pub(crate) fn new(options: &'a LintOptions) -> Result<Self,String>
+    {
+        Ok(Self{
+            options,
+            stateNaming: compileNamingPattern(&options.stateNaming)?,
+            eventNaming: compileNamingPattern(&options.eventNaming)?,
+            allowRegex: Regex::new(r"msm-grapher:\s*allow\(([^)]*)\)").unwrap()
+        })
+    }
+
+    pub(crate) fn lintMachine(&self, machine: &Machine) -> Vec<Finding>
+    {
+        let mut findings = vec![];
+        for rule in LINT_RULES {
+            let severity = match self.options.severityOf(rule) {
+                Some(severity) => severity,
+                None => continue
+            };
+            let ruleFindings = match rule {
+                LintRule::Naming => self.findBadlyNamedElements(machine),
+                LintRule::UnsortedRows => findUnsortedRows(machine),
+                LintRule::DuplicateRow => findDuplicateRows(machine),
+                LintRule::SelfLoopWithoutAction => findSelfLoopsWithoutAction(machine),
+                LintRule::UnguardedNoneEvent => findUnguardedNoneEvents(machine),
+                LintRule::MissingInitialState => findMissingInitialState(machine)
+            };
+            for mut finding in ruleFindings.into_iter().filter(|finding| !self.isSuppressed(machine, finding, rule)) {
+                finding.severity = severity;
+                finding.message += &format!(" [{}]", rule.id());
+                findings.push(finding);
+            }
+        }
+        findings.sort_by_key(|finding| finding.line);
+        findings
+    }
+
+    fn findBadlyNamedElements(&self, machine: &Machine) -> Vec<Finding>
+    {
+        let mut findings: Vec<Finding> = listStates(machine).into_iter()
+            .filter(|state| shouldBeShown(state) && !self.stateNaming.is_match(unqualifiedName(state)))
+            .map(|state| {
+                let message = format!("state {} in {} does not match the naming pattern {}", state, machine.name, self.stateNaming);
+                makeFinding(machine, state, message, |row| row.start == state || row.target == state)
+            })
+            .collect();
+
+        let mut checkedEvents = HashSet::new();
+        for row in machine.rows.iter().filter(|row| shouldBeShown(&row.event) && checkedEvents.insert(&row.event)) {
+            if !self.eventNaming.is_match(unqualifiedName(&row.event)) {
+                let message = format!("event {} in {} does not match the naming pattern {}", row.event, machine.name, self.eventNaming);
+                findings.push(makeFinding(machine, &row.event, message, |otherRow| otherRow.event == row.event));
+            }
+        }
+        findings
+    }
+
+    /// Tells whether a row at the line of the finding has a `// msm-grapher: allow(rule, ...)` comment naming the rule.
+    fn isSuppressed(&self, machine: &Machine, finding: &Finding, rule: LintRule) -> bool
+    {
+        machine.rows.iter()
+            .filter(|row| row.line == finding.line)
+            .flat_map(|row| &row.comments)
+            .flat_map(|comment| self.allowRegex.captures_iter(comment))
+            .any(|captures| captures[1].split(',').any(|id| id.trim() == rule.id()))
+    }
+}
+
+/// Finds rows separated from the earlier rows with the same start state, which makes the table harder to follow.
+fn findUnsortedRows(machine: &Machine) -> Vec<Finding>
+{
+    let mut findings = vec![];
+    for (index, row) in machine.rows.iter().enumerate().skip(1) {
+        if machine.rows[index - 1].start == row.start {
+            continue;
+        }
+        if let Some(earlierRow) = machine.rows[..index].iter().rfind(|earlierRow| earlierRow.start == row.start) {
+            let message = format!("rows starting in {} in {} are not kept together", row.start, machine.name);
+            let notes = vec![FindingNote{line: earlierRow.line, message: format!("previous row starting in {}", row.start)}];
+            findings.push(Finding{severity: Severity::Warning, line: row.line, message, notes});
+        }
+    }
+    findings
+}
+
+fn findDuplicateRows(machine: &Machine) -> Vec<Finding>
+{
+    let mut findings = vec![];
+    for (index, row) in machine.rows.iter().enumerate() {
+        if let Some(earlierRow) = machine.rows[..index].iter().find(|earlierRow| isDuplicate(earlierRow, row)) {
+            let message = format!("duplicate row from {} on {} to {} in {}", row.start, row.event, row.target, machine.name);
+            let notes = vec![FindingNote{line: earlierRow.line, message: "first declared here".into()}];
+            findings.push(Finding{severity: Severity::Warning, line: row.line, message, notes});
+        }
+    }
+    findings
+}
+
+fn isDuplicate(earlierRow: &Row, laterRow: &Row) -> bool
+{
+    earlierRow.start == laterRow.start && earlierRow.event == laterRow.event && earlierRow.target == laterRow.target
+        && earlierRow.action == laterRow.action && earlierRow.guard == laterRow.guard
+}
+
+/// Finds external transitions back to the same state which only exit and enter it again.
+fn findSelfLoopsWithoutAction(machine: &Machine) -> Vec<Finding>
+{
+    machine.rows.iter()
+        .filter(|row| row.start == row.target && row.action.as_ref().is_none_or(|action| flattenActions(action).is_empty()))
+        .map(|row| {
+            let message = format!("transition from {} to itself on {} in {} has no action", row.start, row.event, machine.name);
+            Finding{severity: Severity::Warning, line: row.line, message, notes: vec![]}
+        })
+        .collect()
+}
+
+/// Finds anonymous transitions without a guard whose start state can be entered again after taking them, which
+/// makes boost::msm take them every time the state is entered, possibly forever.
+fn findUnguardedNoneEvents(machine: &Machine) -> Vec<Finding>
+{
+    machine.rows.iter()
+        .filter(|row| !shouldBeShown(&row.event) && row.guard.is_none() && canReach(machine, &row.target, &row.start))
+        .map(|row| {
+            let message = format!("anonymous transition from {} to {} in {} has no guard and may loop forever",
+                row.start, row.target, machine.name);
+            Finding{severity: Severity::Warning, line: row.line, message, notes: vec![]}
+        })
+        .collect()
+}
+
+fn canReach(machine: &Machine, start: &str, target: &str) -> bool
+{
+    let mut visitedStates = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        if state == target {
+            return true;
+        }
+        for row in machine.rows.iter().filter(|row| row.start == state) {
+            if visitedStates.insert(&row.target) {
+                queue.push_back(&row.target);
+            }
+        }
+    }
+    false
+}
+
+fn findMissingInitialState(machine: &Machine) -> Vec<Finding>
+{
+    if machine.declaresInitialState {
+        return vec![];
+    }
+    let message = format!("{} does not declare initial_state, the start of the first row {} is assumed",
+        machine.name, machine.initialStates[0]);
+    vec![Finding{severity: Severity::Warning, line: machine.rows[0].line, message, notes: vec![]}]
+}
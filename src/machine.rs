@@ -0,0 +1,210 @@
+use crate::action_sequence::isSequence;
+use crate::annotations::collectStateLabels;
+use crate::character_reader::CharacterReader;
+use crate::name_resolver::{NameDisplay, resolveNames};
+use crate::row::Row;
+use crate::state_behavior::{StateBehavior, collectStateBehaviors};
+use crate::symbol_table::SymbolTable;
+use crate::token::Token;
+use crate::transition_table_finder::{TransitionTableLocation, findTransitionTables};
+use crate::transition_table_lexer::{LexedTokens, lexTransitionTable};
+use crate::transition_table_parser::{ParseError, ROW_IDENTIFIER_PATTERN, parseTransitionTable};
+use crate::type_expression::TypeExpression;
+use crate::type_expression_parser::parseTypeExpression;
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+
+/// Matches text which may change more than the transition table it is written in: braces and semicolons ending scopes
+/// and declarations, comments, literals, preprocessor directives and keywords of declarations found in the whole text.
+static NON_LOCAL_CHANGE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r#"[{};/*"'#]"#,
+    r"|\b(?:struct|class|namespace|using|typedef|template|transition_table|initial_state|on_entry|on_exit)\b")).unwrap());
+
+#[derive(Debug)]
+pub(crate) struct Machine
+{
+    pub(crate) name: String,
+    /// The name with enclosing namespaces and structs, usable in code outside of them.
+    pub(crate) qualifiedName: String,
+    /// The namespaces enclosing the definition, joined with `::`, in which names of states and events are looked up.
+    pub(crate) namespace: Option<String>,
+    pub(crate) rows: Vec<Row>,
+    /// One state per orthogonal region, taken from `initial_state` or the start of the first row when it is missing.
+    pub(crate) initialStates: Vec<String>,
+    pub(crate) declaresInitialState: bool,
+    /// Display names of states given in `@msm-label` annotations, keyed by the names of their structs.
+    pub(crate) stateLabels: HashMap<String, String>,
+    /// Summaries of `on_entry` and `on_exit` of states, keyed by the qualified names of their structs.
+    pub(crate) stateBehaviors: HashMap<String, StateBehavior>
+}
+
+pub(crate) fn parseMachines(text: &str, nameDisplay: NameDisplay) -> Result<Vec<Machine>,String>
+{
+    ParsedText::parse(text, nameDisplay).tables.into_iter().map(|table| table.machine.map_err(|e| e.message)).collect()
+}
+
+/// Transition tables of a text, each parsed on its own, so that a table can be parsed again after a change inside it.
+pub(crate) struct ParsedText
+{
+    stateLabels: HashMap<String, String>,
+    stateBehaviors: HashMap<String, StateBehavior>,
+    pub(crate) tables: Vec<ParsedTable>
+}
+
+/// A transition table with the machine parsed from it, or the syntax error found in it.
+pub(crate) struct ParsedTable
+{
+    location: TransitionTableLocation,
+    symbols: SymbolTable,
+    /// The byte offset after the semicolon ending the table.
+    end: usize,
+    pub(crate) machine: Result<Machine,ParseError>
+}
+
+impl ParsedText
+{
+    pub(crate) fn parse(text: &str, nameDisplay: NameDisplay) -> Self
+    {
+        let mut parsedText = Self{
+            stateLabels: collectStateLabels(text),
+            stateBehaviors: collectStateBehaviors(text),
+            tables: vec![]
+        };
+        for location in findTransitionTables(text) {
+            let symbols = SymbolTable::collect(text, &location.qualifiedMachineName);
+            let table = parsedText.parseTable(text, location, symbols, nameDisplay);
+            parsedText.tables.push(table);
+        }
+        parsedText
+    }
+
+    /// Returns the machines of all tables, or the first syntax error.
+    pub(crate) fn machines(&self) -> Result<Vec<&Machine>,&ParseError>
+    {
+        self.tables.iter().map(|table| table.machine.as_ref()).collect()
+    }
+
+    /// Parses again only the table containing a change, given as the range of the inserted text within the new text and
+    /// the removed text, and moves the tables following it. Returns false when the change may affect anything outside
+    /// of the table, like names of machines, aliases or other tables, and then the whole text has to be parsed again.
+    pub(crate) fn reparseChangedTable(&mut self, text: &str, inserted: Range<usize>, removedText: &str,
+        nameDisplay: NameDisplay) -> bool
+    {
+        let removedEnd = inserted.start + removedText.len();
+        let isInside = |table: &ParsedTable| table.location.start < inserted.start && removedEnd < table.end;
+        let index = match self.tables.iter().position(isInside) {
+            Some(index) => index,
+            None => return false
+        };
+        let lineStart = text[..inserted.start].rfind('\n').map_or(0, |index| index + 1);
+        let lineEnd = text[inserted.end..].find('\n').map_or(text.len(), |index| inserted.end + index);
+        if NON_LOCAL_CHANGE_REGEX.is_match(&text[lineStart..lineEnd]) || NON_LOCAL_CHANGE_REGEX.is_match(removedText) {
+            return false;
+        }
+
+        let byteShift = inserted.len() as isize - removedText.len() as isize;
+        let lineShift = text[inserted].matches('\n').count() as isize - removedText.matches('\n').count() as isize;
+        let table = self.tables.remove(index);
+        let expectedEnd = table.end.saturating_add_signed(byteShift);
+        let table = self.parseTable(text, table.location, table.symbols, nameDisplay);
+        if table.end != expectedEnd {
+            return false;
+        }
+        self.tables.insert(index, table);
+        self.tables[index + 1..].iter_mut().for_each(|table| table.shift(byteShift, lineShift));
+        true
+    }
+
+    fn parseTable(&self, text: &str, location: TransitionTableLocation, symbols: SymbolTable, nameDisplay: NameDisplay)
+        -> ParsedTable
+    {
+        let characterReader = CharacterReader::new(&text[location.start..]);
+        let lexedTokens = lexTransitionTable(characterReader);
+        let end = location.start + lexedTokens.end;
+        let mut tokens = expandRowAliases(lexedTokens, &symbols);
+        let precedingLineCount = text[..location.start].matches('\n').count();
+        tokens.lines.iter_mut().for_each(|line| *line += precedingLineCount);
+        tokens.spans.iter_mut().for_each(|span| *span = span.start + location.start..span.end + location.start);
+        let machine = parseTransitionTable(&tokens).map(|rows| {
+            let (initialStates, declaresInitialState) = match location.initialState.as_deref().map(parseInitialStates) {
+                Some(Some(states)) => (states, true),
+                _ => (vec![rows[0].start.clone()], false)
+            };
+            let mut machine = Machine{
+                name: location.machineName.clone(),
+                qualifiedName: location.qualifiedMachineName.clone(),
+                namespace: location.machineNamespace.clone(),
+                rows,
+                initialStates,
+                declaresInitialState,
+                stateLabels: self.stateLabels.clone(),
+                stateBehaviors: self.stateBehaviors.clone()
+            };
+            resolveNames(&mut machine, &symbols, nameDisplay);
+            machine
+        });
+        ParsedTable{location, symbols, end, machine}
+    }
+}
+
+impl ParsedTable
+{
+    /// Moves the table after a change before it, which shifted the text by the numbers of bytes and lines.
+    fn shift(&mut self, byteShift: isize, lineShift: isize)
+    {
+        self.location.start = self.location.start.saturating_add_signed(byteShift);
+        self.end = self.end.saturating_add_signed(byteShift);
+        match &mut self.machine {
+            Ok(machine) => machine.rows.iter_mut().for_each(|row| row.line = row.line.saturating_add_signed(lineShift)),
+            Err(e) => {
+                e.line = e.line.saturating_add_signed(lineShift);
+                e.span = e.span.start.saturating_add_signed(byteShift)..e.span.end.saturating_add_signed(byteShift);
+            }
+        }
+    }
+}
+
+/// Replaces rows declared with `typedef` or `using` by their definitions, placed at the line of the alias.
+fn expandRowAliases(lexedTokens: LexedTokens, symbols: &SymbolTable) -> LexedTokens
+{
+    let rowRegex = Regex::new(ROW_IDENTIFIER_PATTERN).unwrap();
+    let mut expandedTokens = LexedTokens::new();
+    let tokens = lexedTokens.tokens.into_iter().zip(lexedTokens.lines).zip(lexedTokens.spans).zip(lexedTokens.comments);
+    for (((token, line), span), comments) in tokens {
+        let row = match &token {
+            Token::Identifier(name) => symbols.findRowAlias(name, &rowRegex),
+            _ => None
+        };
+        match row {
+            Some(row) => {
+                let rowTokens = lexTransitionTable(CharacterReader::new(row)).tokens;
+                expandedTokens.lines.extend(std::iter::repeat_n(line, rowTokens.len()));
+                expandedTokens.spans.extend(std::iter::repeat_n(span, rowTokens.len()));
+                expandedTokens.comments.push(comments);
+                expandedTokens.comments.extend(std::iter::repeat_n(vec![], rowTokens.len() - 1));
+                expandedTokens.tokens.extend(rowTokens);
+            },
+            None => {
+                expandedTokens.tokens.push(token);
+                expandedTokens.lines.push(line);
+                expandedTokens.spans.push(span);
+                expandedTokens.comments.push(comments);
+            }
+        }
+    }
+    expandedTokens
+}
+
+/// Lists initial states of regions from a type like `State1` or `boost::mpl::vector<State1, State2>`.
+fn parseInitialStates(text: &str) -> Option<Vec<String>>
+{
+    match parseTypeExpression(text).ok()? {
+        TypeExpression::Template{name, arguments} if isSequence(&name) && !arguments.is_empty() =>
+            Some(arguments.iter().map(|argument| argument.to_string()).collect()),
+        expression => Some(vec![expression.to_string()])
+    }
+}
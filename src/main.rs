@@ -1,124 +1,138 @@
 #![allow(non_snake_case)]
 
+mod action_sequence;
+mod analysis;
+mod annotations;
+mod batch;
 mod character_reader;
+mod cli;
+mod config;
+mod coverage;
+mod diagram;
+mod diff;
+mod dot_renderer;
 mod flow;
+mod glob;
+mod guard_expression;
+mod input_files;
+mod label_template;
+mod language_server;
+mod lint;
+mod machine;
+mod matrix_renderer;
+mod mermaid_renderer;
+mod name_policy;
+mod name_resolver;
+mod paths;
+mod plantuml_renderer;
+mod renderer;
 mod row;
-mod row_section_parser;
+mod serve;
+mod simulator;
+mod state_behavior;
+mod svg_renderer;
+mod symbol_table;
+mod test_generator;
 mod token;
+mod transition_label;
+mod transition_table_finder;
 mod transition_table_lexer;
 mod transition_table_parser;
-
-use crate::character_reader::CharacterReader;
-use crate::row::Row;
-use crate::transition_table_lexer::lexTransitionTable;
-use crate::transition_table_parser::parseTransitionTable;
-
-use regex::Regex;
-use std::path::PathBuf;
+mod type_expression;
+mod type_expression_parser;
+mod watch;
+
+use crate::analysis::{Report, analyzeInputFiles};
+use crate::batch::generateDiagramsInBatch;
+use crate::cli::{Command, Options, helpText, parseArguments, versionText};
+use crate::coverage::measureCoverage;
+use crate::diagram::buildDiagram;
+use crate::diff::compareFiles;
+use crate::input_files::readInputFile;
+use crate::language_server::runLanguageServer;
+use crate::lint::lintInputFiles;
+use crate::machine::parseMachines;
+use crate::paths::findPaths;
+use crate::renderer::renderDiagram;
+use crate::serve::serveDiagrams;
+use crate::simulator::simulate;
+use crate::test_generator::generateTests;
+use crate::watch::watchInputFiles;
+
+use std::path::Path;
 
 
 fn main() -> Result<(),String>
 {
     let args: Vec<String> = std::env::args().collect();
-    let filePath = match args.len() {
-        0 => return Err("Unexpected no arguments passed to program.".into()),
-        1 => return Err("Please provide a path to a file to analyze.".into()),
-        2 => PathBuf::from(&args[1]),
-        n => return Err(format!("Too many arguments passed to program, expected only one with a file path, got {}", n-1))
-    };
-
-    let fileContent = match std::fs::read_to_string(&filePath) {
-        Ok(content) => content,
-        Err(e) => return Err(format!("Failed to read file: {:?}, error: {}", filePath, e))
-    };
-
-    let startOfTransitionTable = match findStartOfTransitionTable(&fileContent) {
-        Some(start) => start,
-        None => return Err("Transition table was not found.".into())
-    };
-
-    let characterReader = CharacterReader::new(&fileContent[startOfTransitionTable..]);
-    let tokens = lexTransitionTable(characterReader);
-    let rows = match parseTransitionTable(&tokens) {
-        Ok(rows) => rows,
-        Err(e) => return Err(e)
-    };
-
-    let mut outputString = format!("@startuml\nhide empty description\n[*] --> {}\n", rows[0].start);
-    for row in rows {
-        outputString.push_str(&format!("{} --> {}", row.start, row.target));
-        if let Some(transitionText) = makeTransitionText(&row) {
-            outputString.push_str(&transitionText);
-        }
-        outputString.push('\n');
+    match parseArguments(&args)? {
+        Command::PrintHelp => print!("{}", helpText()),
+        Command::PrintVersion => println!("{}", versionText()),
+        Command::Generate(options) => match options.watches {
+            true => watchInputFiles(&options, generate)?,
+            false => generate(&options)?
+        },
+        Command::Analyze(options) => writeReport(options.outputPath.as_deref(), analyzeInputFiles(&options)?)?,
+        Command::FindPath(options, query) => writeOutput(options.outputPath.as_deref(), &findPaths(&options, &query)?)?,
+        Command::GenerateTests(options, framework) =>
+            writeOutput(options.outputPath.as_deref(), &generateTests(&options, framework)?)?,
+        Command::MeasureCoverage(options, query) => {
+            let report = measureCoverage(&options, &query)?;
+            writeOutput(options.outputPath.as_deref(), &report.diagrams)?;
+            eprint!("{}", report.summary);
+        },
+        Command::Simulate(options, simulationOptions) => simulate(&options, &simulationOptions)?,
+        Command::Diff(options, diffOptions) => writeOutput(options.outputPath.as_deref(), &compareFiles(&options, &diffOptions)?)?,
+        Command::Lint(options, lintOptions) => writeReport(options.outputPath.as_deref(), lintInputFiles(&options, &lintOptions)?)?,
+        Command::RunLanguageServer(options) => runLanguageServer(&options)?,
+        Command::Serve(options, serveOptions) => serveDiagrams(&options, &serveOptions)?
     }
-    outputString.push_str("@enduml");
-    println!("{}", outputString);
     Ok(())
 }
 
-fn findStartOfTransitionTable(text: &str) -> Option<usize>
+fn generate(options: &Options) -> Result<(),String>
 {
-    if text.is_empty() {
-        return None;
-    }
-    let transitionTableRegex = Regex::new(r"^\s*struct transition_table\b").unwrap();
-    let mut startIndex = 0;
-    loop {
-        let endIndex = match text[startIndex..].find('\n') {
-            Some(index) => startIndex + index,
-            None => {
-                if text.len() - 1 > startIndex {
-                    text.len()
-                } else {
-                    return None;
-                }
-            }
-        };
-        let textSlice = &text[startIndex..endIndex];
-        if transitionTableRegex.is_match(textSlice) {
-            return Some(startIndex);
-        }
-        startIndex = endIndex + 1;
-        if endIndex >= text.len() {
-            return None;
-        }
+    match &options.outputDirectory {
+        Some(outputDirectory) => generateDiagramsInBatch(options, outputDirectory),
+        None => generateDiagrams(options)
     }
 }
 
-const TRANSITION_PREFIX: &str = " : ";
-
-fn makeTransitionText(row: &Row) -> Option<String>
+fn generateDiagrams(options: &Options) -> Result<(),String>
 {
-    let mut text = String::from(TRANSITION_PREFIX);
-    if shouldBeShown(&row.event) {
-        text.push_str(&format!("on {}", row.event));
+    let fileContent = readInputFile(&options.inputPaths[0])?;
+    let machines = parseMachines(&fileContent, options.nameDisplay)?;
+    if machines.is_empty() {
+        return Err("Transition table was not found.".into());
     }
 
-    if shouldBeShown(&row.guard) {
-        addNewLineIfNeeded(&mut text);
-        text.push_str(&format!("if {}", row.guard));
-    }
-
-    if shouldBeShown(&row.action) {
-        addNewLineIfNeeded(&mut text);
-        text.push_str(&format!("do {}", row.action));
-    }
-
-    match text.as_str() {
-        TRANSITION_PREFIX => None,
-        _ => Some(text)
-    }
+    let diagramOptions = options.makeDiagramOptions()?;
+    let diagrams: Vec<String> = machines.iter()
+        .map(|machine| renderDiagram(&buildDiagram(machine, &diagramOptions), &diagramOptions, options.format))
+        .collect();
+    writeOutput(options.outputPath.as_deref(), &(diagrams.join("\n") + "\n"))
 }
 
-fn shouldBeShown(name: &str) -> bool
+/// Writes the report and fails when it contains errors, so that scripts can stop on them.
+fn writeReport(outputPath: Option<&Path>, report: Report) -> Result<(),String>
 {
-    !matches!(name, "" | "None" | "none" | "front::none" | "msm::front::none" | "boost::msm::front::none")
+    writeOutput(outputPath, &report.text)?;
+    match report.errorCount {
+        0 => Ok(()),
+        errorCount => Err(format!("Found {} error(s).", errorCount))
+    }
 }
 
-fn addNewLineIfNeeded(text: &mut String)
+fn writeOutput(outputPath: Option<&Path>, text: &str) -> Result<(),String>
 {
-    if text != TRANSITION_PREFIX {
-        text.push_str("\\n");
+    match outputPath {
+        Some(path) => match std::fs::write(path, text) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("Failed to write file: {:?}, error: {}", path, e))
+        },
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,126 @@
+use crate::diagram::{Diagram, DiagramTransition};
+use crate::transition_label::shouldBeShown;
+
+
+const NONE_EVENT_HEADER: &str = "(none)";
+const INTERNAL_TRANSITION_TEXT: &str = "(internal)";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TableFormat
+{
+    Markdown,
+    Csv,
+    Html
+}
+
+impl TableFormat
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "markdown" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            "html" => Ok(Self::Html),
+            _ => Err(format!("Unknown table format: {}, expected one of: markdown, csv, html.", name))
+        }
+    }
+}
+
+/// Renders a table with a row per state and a column per event, leaving cells of unhandled combinations blank. Internal
+/// transitions are shown in the rows of their start states, as their `none` target is not a state.
+pub(crate) fn renderMatrix(diagram: &Diagram, format: TableFormat) -> String
+{
+    let mut events: Vec<Option<&str>> = vec![];
+    for transition in &diagram.transitions {
+        let event = transition.label.event.as_deref();
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+
+    let mut header = vec![diagram.name.clone()];
+    header.extend(events.iter().map(|event| event.unwrap_or(NONE_EVENT_HEADER).to_string()));
+    let mut rows = vec![];
+    for state in diagram.states.iter().filter(|state| shouldBeShown(&state.id)) {
+        let mut cells = vec![vec![state.label.clone()]];
+        for event in &events {
+            cells.push(diagram.transitions.iter()
+                .filter(|transition| transition.start == state.id && transition.label.event.as_deref() == *event)
+                .map(|transition| describeTransition(diagram, transition))
+                .collect());
+        }
+        rows.push(cells);
+    }
+
+    match format {
+        TableFormat::Markdown => renderMarkdown(&header, &rows),
+        TableFormat::Csv => renderCsv(&header, &rows),
+        TableFormat::Html => renderHtml(&header, &rows)
+    }
+}
+
+/// Describes a transition as `Target [guard] / action1; action2`, or `(internal) [guard] / action` when it has no target.
+fn describeTransition(diagram: &Diagram, transition: &DiagramTransition) -> String
+{
+    let target = diagram.states.iter().find(|state| state.id == transition.target);
+    let mut text = match (shouldBeShown(&transition.target), target) {
+        (false, _) => INTERNAL_TRANSITION_TEXT.into(),
+        (true, Some(state)) => state.label.clone(),
+        (true, None) => transition.target.clone()
+    };
+    if let Some(guard) = &transition.label.guard {
+        text += &format!(" [{}]", guard);
+    }
+    if !transition.label.actions.is_empty() {
+        text += &format!(" / {}", transition.label.actions.join("; "));
+    }
+    text
+}
+
+fn renderMarkdown(header: &[String], rows: &[Vec<Vec<String>>]) -> String
+{
+    let makeLine = |cells: Vec<String>| -> String {
+        cells.iter().fold(String::from("|"), |line, cell| match cell.is_empty() {
+            true => line + " |",
+            false => line + " " + &cell.replace('|', "\\|") + " |"
+        })
+    };
+    let mut lines = vec![makeLine(header.to_vec()), format!("|{}", " --- |".repeat(header.len()))];
+    for row in rows {
+        lines.push(makeLine(row.iter().map(|lines| lines.join("<br>")).collect()));
+    }
+    lines.join("\n")
+}
+
+fn renderCsv(header: &[String], rows: &[Vec<Vec<String>>]) -> String
+{
+    let quote = |text: &str| match text.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string()
+    };
+    let mut lines = vec![header.iter().map(|cell| quote(cell)).collect::<Vec<String>>().join(",")];
+    for row in rows {
+        lines.push(row.iter().map(|lines| quote(&lines.join("\n"))).collect::<Vec<String>>().join(","));
+    }
+    lines.join("\n")
+}
+
+fn renderHtml(header: &[String], rows: &[Vec<Vec<String>>]) -> String
+{
+    let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    let mut lines = vec![String::from("<table>")];
+    let headerCells: Vec<String> = header.iter().map(|cell| format!("<th>{}</th>", escape(cell))).collect();
+    lines.push(format!("  <tr>{}</tr>", headerCells.join("")));
+    for row in rows {
+        let mut cells = vec![format!("<th>{}</th>", escape(&row[0].join("")))];
+        for lines in &row[1..] {
+            cells.push(match lines.is_empty() {
+                true => String::from("<td style=\"background-color: #fdd\"></td>"),
+                false => format!("<td>{}</td>", lines.iter().map(|line| escape(line)).collect::<Vec<String>>().join("<br>"))
+            });
+        }
+        lines.push(format!("  <tr>{}</tr>", cells.join("")));
+    }
+    lines.push(String::from("</table>"));
+    lines.join("\n")
+}
@@ -0,0 +1,70 @@
+use crate::diagram::{Diagram, makeIdentifier};
+
+
+pub(crate) fn renderMermaid(diagram: &Diagram) -> String
+{
+    let mut lines = vec![String::from("stateDiagram-v2")];
+    for state in &diagram.states {
+        if makeIdentifier(&state.id) != state.id || state.label != state.id {
+            lines.push(format!("    state \"{}\" as {}", escape(&state.label), makeIdentifier(&state.id)));
+        }
+    }
+    for state in &diagram.states {
+        for description in &state.descriptions {
+            lines.push(format!("    {} : {}", makeIdentifier(&state.id), escape(description)));
+        }
+    }
+    for initialState in &diagram.initialStates {
+        lines.push(format!("    [*] --> {}", makeIdentifier(initialState)));
+    }
+    for transition in &diagram.transitions {
+        let mut line = format!("    {} --> {}", makeIdentifier(&transition.start), makeIdentifier(&transition.target));
+        let label: Vec<String> = transition.labelLines.iter().map(String::as_str)
+            .chain(transition.note.iter().flat_map(|note| note.lines()))
+            .map(escape)
+            .collect();
+        if !label.is_empty() {
+            line.push_str(&format!(" : {}", label.join("<br>")));
+        }
+        lines.push(line);
+    }
+    lines.extend(makeColorClasses(diagram));
+    lines.join("\n")
+}
+
+/// State diagrams in Mermaid cannot colour transitions, so only states get a class per colour.
+fn makeColorClasses(diagram: &Diagram) -> Vec<String>
+{
+    let mut colors: Vec<&str> = vec![];
+    for color in diagram.states.iter().filter_map(|state| state.color.as_deref()) {
+        if !colors.contains(&color) {
+            colors.push(color);
+        }
+    }
+    let mut lines = vec![];
+    for (index, color) in colors.iter().enumerate() {
+        let states: Vec<String> = diagram.states.iter()
+            .filter(|state| state.color.as_deref() == Some(color))
+            .map(|state| makeIdentifier(&state.id))
+            .collect();
+        lines.push(format!("    classDef color{} stroke:{},stroke-width:3px", index, color));
+        lines.push(format!("    class {} color{}", states.join(","), index));
+    }
+    lines
+}
+
+/// Replaces characters with entity codes in a single pass, so that semicolons ending the inserted codes stay intact.
+fn escape(text: &str) -> String
+{
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("#quot;"),
+            '<' => escaped.push_str("#lt;"),
+            '>' => escaped.push_str("#gt;"),
+            ';' => escaped.push_str("#59;"),
+            _ => escaped.push(character)
+        }
+    }
+    escaped
+}
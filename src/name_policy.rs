@@ -0,0 +1,143 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+
+pub(crate) struct NamePolicy
+{
+    pub(crate) namespaceStripping: NamespaceStripping,
+    pub(crate) templateDepth: Option<usize>,
+    pub(crate) labels: HashMap<String, String>,
+    identifierRegex: Regex
+}
+
+#[derive(Clone)]
+pub(crate) enum NamespaceStripping
+{
+    Nothing,
+    All,
+    Prefixes(Vec<String>)
+}
+
+impl NamePolicy
+{
+    pub(crate) fn new() -> Self
+    {
+        Self{
+            namespaceStripping: NamespaceStripping::Nothing,
+            templateDepth: None,
+            labels: HashMap::new(),
+            identifierRegex: Regex::new(r"(?:::)?[A-Za-z_]\w*(?:::[A-Za-z_]\w*)*").unwrap()
+        }
+    }
+
+    /// Formats a state, event, guard or action name for display. Labels are looked up first for the whole name,
+    /// then for each name nested in it, before namespaces are stripped and template arguments collapsed.
+    pub(crate) fn apply(&self, name: &str) -> String
+    {
+        if let Some(label) = self.findLabel(name) {
+            return label.clone();
+        }
+        let text = self.identifierRegex.replace_all(name, |captures: &regex::Captures| {
+            let identifier = &captures[0];
+            match self.findLabel(identifier) {
+                Some(label) => label.clone(),
+                None => self.stripNamespaces(identifier)
+            }
+        });
+        match self.templateDepth {
+            Some(depth) => collapseTemplateArguments(&text, depth),
+            None => text.into_owned()
+        }
+    }
+
+    /// Tells whether a name given by a user or found in a log refers to `name`, by being written the same way, as
+    /// displayed, or without namespaces.
+    pub(crate) fn isMatch(&self, name: &str, givenName: &str) -> bool
+    {
+        name == givenName || self.apply(name) == givenName || stripAllNamespaces(name) == givenName
+    }
+
+    pub(crate) fn findLabel(&self, name: &str) -> Option<&String>
+    {
+        self.labels.get(name).or_else(|| self.labels.get(stripAllNamespaces(name)))
+    }
+
+    fn stripNamespaces(&self, identifier: &str) -> String
+    {
+        match &self.namespaceStripping {
+            NamespaceStripping::Nothing => identifier.into(),
+            NamespaceStripping::All => stripAllNamespaces(identifier).into(),
+            NamespaceStripping::Prefixes(prefixes) => {
+                let identifier = identifier.trim_start_matches("::");
+                for prefix in prefixes {
+                    let prefix = prefix.trim_start_matches("::").trim_end_matches("::");
+                    if let Some(rest) = identifier.strip_prefix(prefix)
+                        && let Some(rest) = rest.strip_prefix("::") {
+                        return rest.into();
+                    }
+                }
+                identifier.into()
+            }
+        }
+    }
+}
+
+pub(crate) fn loadLabels(path: &Path) -> Result<HashMap<String, String>,String>
+{
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return Err(format!("Failed to read labels file: {:?}, error: {}", path, e))
+    };
+    let table: toml::Table = match content.parse() {
+        Ok(table) => table,
+        Err(e) => return Err(format!("Failed to parse labels file: {:?}, error: {}", path, e))
+    };
+    let labels = match table.get("labels") {
+        Some(toml::Value::Table(labels)) => labels,
+        Some(_) => return Err(format!("Labels file {:?} should contain a [labels] table.", path)),
+        None => &table
+    };
+    labels.iter()
+        .map(|(name, label)| match label {
+            toml::Value::String(label) => Ok((name.clone(), label.clone())),
+            _ => Err(format!("Label for {} in file {:?} should be a string.", name, path))
+        })
+        .collect()
+}
+
+fn stripAllNamespaces(name: &str) -> &str
+{
+    match name.rfind("::") {
+        Some(index) => &name[index + 2..],
+        None => name
+    }
+}
+
+fn collapseTemplateArguments(text: &str, maxDepth: usize) -> String
+{
+    let mut output = String::new();
+    let mut depth = 0;
+    for character in text.chars() {
+        match character {
+            '<' => {
+                depth += 1;
+                if depth <= maxDepth {
+                    output.push('<');
+                } else if depth == maxDepth + 1 {
+                    output.push_str("<...");
+                }
+            },
+            '>' => {
+                if depth <= maxDepth + 1 {
+                    output.push('>');
+                }
+                depth = depth.saturating_sub(1);
+            },
+            _ => if depth <= maxDepth {
+                output.push(character);
+            }
+        }
+    }
+    output
+}
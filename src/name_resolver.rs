@@ -0,0 +1,118 @@
+use crate::action_sequence::isActionSequence;
+use crate::guard_expression::isCombinator;
+use crate::machine::Machine;
+use crate::symbol_table::SymbolTable;
+use crate::transition_label::shouldBeShown;
+use crate::type_expression::TypeExpression;
+
+use std::collections::HashMap;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum NameDisplay
+{
+    Alias,
+    Canonical
+}
+
+impl NameDisplay
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "alias" => Ok(Self::Alias),
+            "canonical" => Ok(Self::Canonical),
+            _ => Err(format!("Unknown name display: {}, expected one of: alias, canonical.", name))
+        }
+    }
+}
+
+/// Makes every state and event appear under a single name, so that aliases of the same type are not shown as
+/// separate ones. With `NameDisplay::Alias` the first spelling found in the table is kept.
+pub(crate) fn resolveNames(machine: &mut Machine, symbols: &SymbolTable, display: NameDisplay)
+{
+    let mut states = NameRegistry::new(display);
+    let mut events = NameRegistry::new(display);
+    for row in &mut machine.rows {
+        row.start = states.resolve(&row.start, symbols);
+        row.event = match shouldBeShown(&symbols.canonicalizeText(&row.event)) {
+            true => events.resolve(&row.event, symbols),
+            false => symbols.canonicalizeText(&row.event)
+        };
+        row.target = states.resolve(&row.target, symbols);
+        row.action = resolveFunctor(row.action.take(), symbols, display).map(|action| resolveStructure(&action, symbols, 0));
+        row.guard = resolveFunctor(row.guard.take(), symbols, display).map(|guard| resolveStructure(&guard, symbols, 0));
+    }
+    for state in &mut machine.initialStates {
+        *state = states.resolve(state, symbols);
+    }
+}
+
+fn resolveFunctor(functor: Option<TypeExpression>, symbols: &SymbolTable, display: NameDisplay) -> Option<TypeExpression>
+{
+    let functor = functor?;
+    let canonical = functor.mapNames(&mut |name| symbols.canonicalizeText(name));
+    if !shouldBeShown(&canonical.to_string()) {
+        return None;
+    }
+    match display {
+        NameDisplay::Alias => Some(functor),
+        NameDisplay::Canonical => Some(canonical)
+    }
+}
+
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+/// Replaces aliases of the boolean functor combinators and of action sequences with what they stand for, so that they
+/// can be recognized. Other names are kept as they were written.
+fn resolveStructure(functor: &TypeExpression, symbols: &SymbolTable, depth: usize) -> TypeExpression
+{
+    let (name, arguments) = match functor {
+        TypeExpression::Template{name, arguments} => (name, arguments),
+        _ => return functor.clone()
+    };
+    let arguments: Vec<TypeExpression> = arguments.iter().map(|argument| resolveStructure(argument, symbols, depth)).collect();
+
+    let canonicalName = symbols.canonicalizeText(name);
+    if isStructural(&canonicalName, arguments.len()) {
+        return TypeExpression::Template{name: canonicalName, arguments};
+    }
+    if depth < MAX_EXPANSION_DEPTH
+        && let Some(expansion) = symbols.expandTemplateAlias(name, &arguments) {
+        let expansion = resolveStructure(&expansion, symbols, depth + 1);
+        if let TypeExpression::Template{name, arguments} = &expansion
+            && isStructural(name, arguments.len()) {
+            return expansion;
+        }
+    }
+    TypeExpression::Template{name: name.clone(), arguments}
+}
+
+fn isStructural(name: &str, argumentCount: usize) -> bool
+{
+    isCombinator(name, argumentCount) || isActionSequence(name, argumentCount)
+}
+
+struct NameRegistry
+{
+    display: NameDisplay,
+    displayedNames: HashMap<String, String>
+}
+
+impl NameRegistry
+{
+    fn new(display: NameDisplay) -> Self
+    {
+        Self{display, displayedNames: HashMap::new()}
+    }
+
+    fn resolve(&mut self, name: &str, symbols: &SymbolTable) -> String
+    {
+        let canonical = symbols.canonicalizeText(name);
+        let displayedName = match self.display {
+            NameDisplay::Alias => name.to_string(),
+            NameDisplay::Canonical => canonical.clone()
+        };
+        self.displayedNames.entry(canonical).or_insert(displayedName).clone()
+    }
+}
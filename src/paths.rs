@@ -0,0 +1,154 @@
+use crate::action_sequence::ActionListLayout;
+use crate::analysis::listStates;
+use crate::cli::{Options, PathQuery};
+use crate::diagram::{DiagramOptions, buildDiagram};
+use crate::input_files::readInputFile;
+use crate::machine::{Machine, parseMachines};
+use crate::renderer::renderDiagram;
+use crate::row::Row;
+use crate::transition_label::{TransitionLabel, shouldBeShown};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+
+const HIGHLIGHT_COLOR: &str = "red";
+
+/// Indices of rows forming a path, in the order of transitions.
+pub(crate) type Path = Vec<usize>;
+
+/// Prints paths between two states of the first machine containing both, or a diagram with the paths highlighted.
+pub(crate) fn findPaths(options: &Options, query: &PathQuery) -> Result<String,String>
+{
+    let fileContent = readInputFile(&options.inputPaths[0])?;
+    let machines = parseMachines(&fileContent, options.nameDisplay)?;
+    if machines.is_empty() {
+        return Err("Transition table was not found.".into());
+    }
+
+    let diagramOptions = options.makeDiagramOptions()?;
+    let (machine, start, target) = machines.iter()
+        .find_map(|machine| Some((machine, findState(machine, &query.start, &diagramOptions)?, findState(machine, &query.target, &diagramOptions)?)))
+        .ok_or_else(|| format!("States {} and {} were not found together in any transition table.", query.start, query.target))?;
+    let paths = match query.maxLength {
+        Some(maxLength) => findSimplePaths(machine, start, target, maxLength),
+        None => findShortestPath(machine, start, target).into_iter().collect()
+    };
+    if paths.is_empty() {
+        return Err(format!("No path from {} to {} was found in {}.", start, target, machine.name));
+    }
+
+    match query.highlight {
+        true => {
+            let mut diagram = buildDiagram(machine, &diagramOptions);
+            let mut states = vec![start];
+            for &rowIndex in paths.iter().flatten() {
+                diagram.transitions[rowIndex].color = Some(HIGHLIGHT_COLOR.into());
+                states.push(&machine.rows[rowIndex].target);
+            }
+            diagram.colorStates(&states, HIGHLIGHT_COLOR);
+            Ok(renderDiagram(&diagram, &diagramOptions, options.format) + "\n")
+        },
+        false => Ok(describePaths(machine, &paths, query.maxLength.is_some(), &diagramOptions))
+    }
+}
+
+/// Finds a path with the fewest transitions, which contains at least one transition even when both states are the same.
+pub(crate) fn findShortestPath(machine: &Machine, start: &str, target: &str) -> Option<Path>
+{
+    let mut reachingRows: HashMap<&str, usize> = HashMap::new();
+    let mut visitedStates = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        for (rowIndex, row) in leavingRows(machine, state) {
+            if row.target == target {
+                let mut path = vec![rowIndex];
+                let mut current = state;
+                while current != start {
+                    let previousRowIndex = reachingRows[current];
+                    path.push(previousRowIndex);
+                    current = &machine.rows[previousRowIndex].start;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if visitedStates.insert(&row.target) {
+                reachingRows.insert(&row.target, rowIndex);
+                queue.push_back(&row.target);
+            }
+        }
+    }
+    None
+}
+
+/// Finds paths without repeated states of at most `maxLength` transitions, sorted by their length.
+pub(crate) fn findSimplePaths(machine: &Machine, start: &str, target: &str, maxLength: usize) -> Vec<Path>
+{
+    let mut paths = vec![];
+    let mut visitedStates = HashSet::from([start]);
+    collectSimplePaths(machine, start, target, maxLength, &mut vec![], &mut visitedStates, &mut paths);
+    paths.sort_by_key(|path| path.len());
+    paths
+}
+
+fn collectSimplePaths<'a>(machine: &'a Machine, state: &str, target: &str, maxLength: usize, currentPath: &mut Path,
+    visitedStates: &mut HashSet<&'a str>, paths: &mut Vec<Path>)
+{
+    if currentPath.len() >= maxLength {
+        return;
+    }
+    for (rowIndex, row) in leavingRows(machine, state) {
+        currentPath.push(rowIndex);
+        if row.target == target {
+            paths.push(currentPath.clone());
+        } else if visitedStates.insert(&row.target) {
+            collectSimplePaths(machine, &row.target, target, maxLength, currentPath, visitedStates, paths);
+            visitedStates.remove(row.target.as_str());
+        }
+        currentPath.pop();
+    }
+}
+
+/// Lists rows leaving the state with their indices. Internal transitions stay in the state, so they never shorten a path
+/// and are skipped.
+fn leavingRows<'a>(machine: &'a Machine, state: &str) -> impl Iterator<Item = (usize, &'a Row)>
+{
+    machine.rows.iter().enumerate().filter(move |(_, row)| row.start == state && shouldBeShown(&row.target))
+}
+
+/// Finds a state by its name as written in the table, as displayed, or without namespaces.
+fn findState<'a>(machine: &'a Machine, name: &str, options: &DiagramOptions) -> Option<&'a str>
+{
+    let states = listStates(machine).into_iter();
+    let matchers: [&dyn Fn(&str) -> bool; 3] = [
+        &|state| state == name,
+        &|state| options.namePolicy.apply(state) == name,
+        &|state| state.rsplit("::").next() == Some(name)
+    ];
+    matchers.iter().find_map(|isMatch| states.clone().find(|state| isMatch(state)))
+}
+
+fn describePaths(machine: &Machine, paths: &[Path], isListingAllPaths: bool, options: &DiagramOptions) -> String
+{
+    let namePolicy = &options.namePolicy;
+    let firstRow = &machine.rows[paths[0][0]];
+    let lastRow = &machine.rows[paths[0][paths[0].len() - 1]];
+    let (start, target) = (namePolicy.apply(&firstRow.start), namePolicy.apply(&lastRow.target));
+    let mut blocks = vec![];
+    for (pathIndex, path) in paths.iter().enumerate() {
+        let mut lines = vec![match isListingAllPaths {
+            true => format!("Path {} from {} to {} in {}:", pathIndex + 1, start, target, machine.name),
+            false => format!("Shortest path from {} to {} in {}:", start, target, machine.name)
+        }];
+        for &rowIndex in path {
+            let row = &machine.rows[rowIndex];
+            let mut line = format!("  {} --> {}", namePolicy.apply(&row.start), namePolicy.apply(&row.target));
+            let labelLines = TransitionLabel::new(row, options).makeLines(ActionListLayout::Inline);
+            if !labelLines.is_empty() {
+                line += &format!(" : {}", labelLines.join(", "));
+            }
+            lines.push(line);
+        }
+        blocks.push(lines.join("\n") + "\n");
+    }
+    blocks.join("\n")
+}
@@ -0,0 +1,132 @@
+use crate::diagram::{Diagram, makeIdentifier};
+
+
+/// Settings of the appearance of PlantUML diagrams, which other formats do not have.
+#[derive(Clone)]
+pub(crate) struct PlantUmlStyle
+{
+    pub(crate) theme: Option<String>,
+    pub(crate) skinparams: Vec<(String, String)>,
+    pub(crate) direction: LayoutDirection,
+    pub(crate) showsLegend: bool,
+    /// Text inserted after the preamble of the diagram.
+    pub(crate) header: Option<String>,
+    /// Text inserted before the end of the diagram.
+    pub(crate) footer: Option<String>
+}
+
+impl PlantUmlStyle
+{
+    pub(crate) fn new() -> Self
+    {
+        Self{theme: None, skinparams: vec![], direction: LayoutDirection::TopDown, showsLegend: false, header: None, footer: None}
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LayoutDirection
+{
+    TopDown,
+    LeftRight
+}
+
+impl LayoutDirection
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "top-down" => Ok(Self::TopDown),
+            "left-right" => Ok(Self::LeftRight),
+            _ => Err(format!("Unknown layout direction: {}, expected one of: top-down, left-right.", name))
+        }
+    }
+}
+
+pub(crate) fn renderPlantUml(diagram: &Diagram, style: &PlantUmlStyle) -> String
+{
+    let mut outputString = String::from("@startuml\n");
+    if let Some(theme) = &style.theme {
+        outputString.push_str(&format!("!theme {}\n", theme));
+    }
+    outputString.push_str("hide empty description\n");
+    if style.direction == LayoutDirection::LeftRight {
+        outputString.push_str("left to right direction\n");
+    }
+    for (name, value) in &style.skinparams {
+        outputString.push_str(&format!("skinparam {} {}\n", name, value));
+    }
+    if let Some(header) = &style.header {
+        outputString.push_str(&endWithNewline(header));
+    }
+    for state in &diagram.states {
+        let color = match &state.color {
+            Some(color) => format!(" {}", formatColor(color)),
+            None => String::new()
+        };
+        if makeIdentifier(&state.id) != state.id || state.label != state.id {
+            outputString.push_str(&format!("state \"{}\" as {}{}\n", escape(&state.label), makeIdentifier(&state.id), color));
+        } else if !color.is_empty() {
+            outputString.push_str(&format!("state {}{}\n", state.id, color));
+        }
+    }
+    for state in &diagram.states {
+        for description in &state.descriptions {
+            outputString.push_str(&format!("{} : {}\n", makeIdentifier(&state.id), description));
+        }
+    }
+    for initialState in &diagram.initialStates {
+        outputString.push_str(&format!("[*] --> {}\n", makeIdentifier(initialState)));
+    }
+    for transition in &diagram.transitions {
+        let arrow = match &transition.color {
+            Some(color) => format!("-[{}]->", formatColor(color)),
+            None => String::from("-->")
+        };
+        outputString.push_str(&format!("{} {} {}", makeIdentifier(&transition.start), arrow, makeIdentifier(&transition.target)));
+        if !transition.labelLines.is_empty() {
+            outputString.push_str(&format!(" : {}", transition.labelLines.join("\\n")));
+        }
+        outputString.push('\n');
+        if let Some(note) = &transition.note {
+            outputString.push_str(&format!("note on link\n{}\nend note\n", note));
+        }
+    }
+    if style.showsLegend && !diagram.events.is_empty() {
+        outputString.push_str("legend right\nEvents:\n");
+        for event in &diagram.events {
+            match &event.color {
+                Some(color) => outputString.push_str(&format!("<color:{}>{}</color>\n", formatColor(color), event.label)),
+                None => outputString.push_str(&format!("{}\n", event.label))
+            }
+        }
+        outputString.push_str("endlegend\n");
+    }
+    if let Some(footer) = &style.footer {
+        outputString.push_str(&endWithNewline(footer));
+    }
+    outputString.push_str("@enduml");
+    outputString
+}
+
+/// Escapes a text put in quotes, writing quotes as Unicode code points and line breaks as `\n`, which PlantUML shows as
+/// new lines.
+fn escape(text: &str) -> String
+{
+    text.replace('"', "<U+0022>").replace("\r\n", "\\n").replace('\n', "\\n")
+}
+
+fn endWithNewline(text: &str) -> String
+{
+    match text.ends_with('\n') {
+        true => text.into(),
+        false => format!("{}\n", text)
+    }
+}
+
+fn formatColor(color: &str) -> String
+{
+    match color.starts_with('#') {
+        true => color.into(),
+        false => format!("#{}", color)
+    }
+}
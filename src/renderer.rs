@@ -0,0 +1,51 @@
+use crate::diagram::{Diagram, DiagramOptions};
+use crate::dot_renderer::renderDot;
+use crate::matrix_renderer::{TableFormat, renderMatrix};
+use crate::mermaid_renderer::renderMermaid;
+use crate::plantuml_renderer::renderPlantUml;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum OutputFormat
+{
+    PlantUml,
+    Dot,
+    Mermaid,
+    Matrix(TableFormat)
+}
+
+impl OutputFormat
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "plantuml" => Ok(Self::PlantUml),
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            "matrix" => Ok(Self::Matrix(TableFormat::Markdown)),
+            _ => Err(format!("Unknown output format: {}, expected one of: plantuml, dot, mermaid, matrix.", name))
+        }
+    }
+
+    pub(crate) fn fileExtension(self) -> &'static str
+    {
+        match self {
+            Self::PlantUml => "puml",
+            Self::Dot => "dot",
+            Self::Mermaid => "mmd",
+            Self::Matrix(TableFormat::Markdown) => "md",
+            Self::Matrix(TableFormat::Csv) => "csv",
+            Self::Matrix(TableFormat::Html) => "html"
+        }
+    }
+}
+
+pub(crate) fn renderDiagram(diagram: &Diagram, options: &DiagramOptions, format: OutputFormat) -> String
+{
+    match format {
+        OutputFormat::PlantUml => renderPlantUml(diagram, &options.plantUmlStyle),
+        OutputFormat::Dot => renderDot(diagram),
+        OutputFormat::Mermaid => renderMermaid(diagram),
+        OutputFormat::Matrix(tableFormat) => renderMatrix(diagram, tableFormat)
+    }
+}
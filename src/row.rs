@@ -1,23 +1,30 @@
-#[derive(Debug)]
+use crate::type_expression::TypeExpression;
+
+
+#[derive(Clone, Debug)]
 pub(crate) struct Row
 {
     pub(crate) kind: RowKind,
     pub(crate) start: String,
     pub(crate) event: String,
     pub(crate) target: String,
-    pub(crate) action: String,
-    pub(crate) guard: String
+    pub(crate) action: Option<TypeExpression>,
+    pub(crate) guard: Option<TypeExpression>,
+    /// The number of the line in the analyzed file at which the row starts, counted from 1.
+    pub(crate) line: usize,
+    /// Texts of `//` comments written above the row or next to any of its parts.
+    pub(crate) comments: Vec<String>
 }
 
 impl Row
 {
-    pub(crate) fn new(kind: RowKind) -> Self
+    pub(crate) fn new(kind: RowKind, line: usize) -> Self
     {
-        Self{kind, start: "".into(), event: "".into(), target: "".into(), action: "".into(), guard: "".into()}
+        Self{kind, start: "".into(), event: "".into(), target: "".into(), action: None, guard: None, line, comments: vec![]}
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) enum RowKind
 {
     WithGuard,
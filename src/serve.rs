@@ -0,0 +1,247 @@
+use crate::analysis::{Finding, displayPath};
+use crate::cli::Options;
+use crate::diagram::buildDiagram;
+use crate::input_files::{collectInputFiles, readInputFile};
+use crate::lint::{LintOptions, Linter};
+use crate::machine::parseMachines;
+use crate::svg_renderer::{SvgRenderer, renderSvg};
+use crate::watch::FileWatcher;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+
+const DEFAULT_PORT: u16 = 8080;
+/// Time after which a comment is sent to streams of events, so that the ones closed by browsers are noticed.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const PAGE_STYLE: &str = "\
+body { display: flex; margin: 0; font-family: sans-serif; }
+nav { width: 22em; min-height: 100vh; padding: 1em; box-sizing: border-box; background: #f4f4f4; }
+nav ul { padding-left: 1.2em; }
+main { flex: 1; padding: 1em; overflow: auto; }
+section { margin-bottom: 2em; }
+.path { color: #777; font-size: small; }
+.findings li { font-size: small; }
+.warning { color: #a60; }
+.error { color: #c00; }";
+const PAGE_SCRIPT: &str = r#"new EventSource("/events").onmessage = () => location.reload();"#;
+
+/// Settings of the serve command.
+pub(crate) struct ServeOptions
+{
+    pub(crate) port: u16,
+    pub(crate) svgRenderer: SvgRenderer,
+    /// Rules checked to list findings of lint next to the machines.
+    pub(crate) lintOptions: LintOptions
+}
+
+impl ServeOptions
+{
+    pub(crate) fn new() -> Self
+    {
+        Self{port: DEFAULT_PORT, svgRenderer: SvgRenderer::BuiltIn, lintOptions: LintOptions::new()}
+    }
+}
+
+/// The number of times the watched files changed, which streams of events wait on.
+struct ChangeCounter
+{
+    count: Mutex<u64>,
+    changed: Condvar
+}
+
+/// A machine shown on the page, with its diagram or the error of rendering it.
+struct MachineView
+{
+    name: String,
+    path: String,
+    svg: Result<String,String>,
+    findings: Vec<Finding>
+}
+
+/// Serves a page with diagrams of the machines found in the input files on localhost, until the program is interrupted.
+/// The page is rendered anew on every request and browsers showing it are told to reload it when the files change.
+pub(crate) fn serveDiagrams(options: &Options, serveOptions: &ServeOptions) -> Result<(),String>
+{
+    Linter::new(&serveOptions.lintOptions)?;
+    let mut watcher = FileWatcher::new(options)?;
+    let listener = match TcpListener::bind(("127.0.0.1", serveOptions.port)) {
+        Ok(listener) => listener,
+        Err(e) => return Err(format!("Failed to listen on port {}, error: {}", serveOptions.port, e))
+    };
+    match listener.local_addr() {
+        Ok(address) => println!("Serving diagrams at http://{}/, press Ctrl+C to stop.", address),
+        Err(e) => return Err(format!("Failed to listen on port {}, error: {}", serveOptions.port, e))
+    }
+
+    let changes = ChangeCounter{count: Mutex::new(0), changed: Condvar::new()};
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            loop {
+                if let Err(e) = watcher.waitForChange() {
+                    eprintln!("Error: {:?}", e);
+                    return;
+                }
+                *changes.count.lock().unwrap() += 1;
+                changes.changed.notify_all();
+            }
+        });
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let changes = &changes;
+                    // Errors only mean that the browser closed the connection.
+                    scope.spawn(move || { let _ = handleConnection(stream, options, serveOptions, changes); });
+                },
+                Err(e) => eprintln!("Error: {:?}", format!("Failed to accept a connection, error: {}", e))
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handleConnection(stream: TcpStream, options: &Options, serveOptions: &ServeOptions, changes: &ChangeCounter)
+    -> std::io::Result<()>
+{
+    let mut reader = BufReader::new(&stream);
+    let mut requestLine = String::new();
+    reader.read_line(&mut requestLine)?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = requestLine.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    if method != "GET" {
+        return respond(&stream, "405 Method Not Allowed", "text/plain", "Only GET requests are supported.\n");
+    }
+    match target.split('?').next().unwrap_or_default() {
+        "/" => respond(&stream, "200 OK", "text/html", &renderPage(options, serveOptions)),
+        "/events" => streamChanges(&stream, changes),
+        _ => respond(&stream, "404 Not Found", "text/plain", "Not found.\n")
+    }
+}
+
+fn respond(mut stream: &TcpStream, status: &str, contentType: &str, body: &str) -> std::io::Result<()>
+{
+    write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, contentType, body.len(), body)?;
+    stream.flush()
+}
+
+/// Sends server-sent events asking to reload the page whenever the watched files change, until the browser disconnects.
+fn streamChanges(mut stream: &TcpStream, changes: &ChangeCounter) -> std::io::Result<()>
+{
+    write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n")?;
+    stream.flush()?;
+    let mut seenCount = *changes.count.lock().unwrap();
+    loop {
+        let count = {
+            let count = changes.count.lock().unwrap();
+            *changes.changed.wait_timeout_while(count, KEEP_ALIVE_INTERVAL, |count| *count == seenCount).unwrap().0
+        };
+        match count == seenCount {
+            true => write!(stream, ": keep-alive\n\n")?,
+            false => {
+                seenCount = count;
+                write!(stream, "data: reload\n\n")?;
+            }
+        }
+        stream.flush()?;
+    }
+}
+
+/// Renders a page with a sidebar listing machines with findings of lint and errors of reading files, next to diagrams.
+fn renderPage(options: &Options, serveOptions: &ServeOptions) -> String
+{
+    let (machines, errors) = collectMachineViews(options, serveOptions);
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n",
+        env!("CARGO_PKG_NAME"), PAGE_STYLE);
+    html += "<nav>\n<h1>Machines</h1>\n<ul>\n";
+    for (index, machine) in machines.iter().enumerate() {
+        html += &format!("<li><a href=\"#machine-{}\">{}</a> <span class=\"path\">{}</span>",
+            index, escape(&machine.name), escape(&machine.path));
+        if !machine.findings.is_empty() {
+            html += "\n<ul class=\"findings\">\n";
+            for finding in &machine.findings {
+                html += &format!("<li class=\"{}\">line {}: {}</li>\n", finding.severity.name(), finding.line, escape(&finding.message));
+            }
+            html += "</ul>\n";
+        }
+        html += "</li>\n";
+    }
+    html += "</ul>\n";
+    if !errors.is_empty() {
+        html += "<h2>Errors</h2>\n<ul>\n";
+        for error in &errors {
+            html += &format!("<li class=\"error\">{}</li>\n", escape(error));
+        }
+        html += "</ul>\n";
+    }
+    html += "</nav>\n<main>\n";
+    if machines.is_empty() {
+        html += "<p>Transition table was not found.</p>\n";
+    }
+    for (index, machine) in machines.iter().enumerate() {
+        html += &format!("<section id=\"machine-{}\">\n<h2>{}</h2>\n", index, escape(&machine.name));
+        match &machine.svg {
+            Ok(svg) => html += svg,
+            Err(e) => html += &format!("<p class=\"error\">{}</p>", escape(e))
+        }
+        html += "\n</section>\n";
+    }
+    html + &format!("</main>\n<script>{}</script>\n</body>\n</html>\n", PAGE_SCRIPT)
+}
+
+/// Reads machines from the input files, keeping errors of single files so that the others are still shown.
+fn collectMachineViews(options: &Options, serveOptions: &ServeOptions) -> (Vec<MachineView>, Vec<String>)
+{
+    let mut views = vec![];
+    let diagramOptions = match options.makeDiagramOptions() {
+        Ok(diagramOptions) => diagramOptions,
+        Err(e) => return (views, vec![e])
+    };
+    let linter = match Linter::new(&serveOptions.lintOptions) {
+        Ok(linter) => linter,
+        Err(e) => return (views, vec![e])
+    };
+    let inputFiles = match collectInputFiles(&options.inputPaths, &options.includePatterns, &options.excludePatterns) {
+        Ok(inputFiles) => inputFiles,
+        Err(e) => return (views, vec![e])
+    };
+
+    let mut errors = vec![];
+    for inputFile in inputFiles {
+        let path = displayPath(&inputFile.path);
+        let machines = match readInputFile(&inputFile.path).and_then(|content| parseMachines(&content, options.nameDisplay)) {
+            Ok(machines) => machines,
+            Err(e) => {
+                errors.push(format!("{}: {}", path, e));
+                continue;
+            }
+        };
+        if machines.is_empty() && !inputFile.wasFoundInDirectory {
+            errors.push(format!("{}: Transition table was not found.", path));
+        }
+        for machine in &machines {
+            let idPrefix = format!("machine-{}", views.len());
+            views.push(MachineView{
+                name: machine.name.clone(),
+                path: path.clone(),
+                svg: renderSvg(&buildDiagram(machine, &diagramOptions), serveOptions.svgRenderer, &idPrefix),
+                findings: linter.lintMachine(machine)
+            });
+        }
+    }
+    (views, errors)
+}
+
+fn escape(text: &str) -> String
+{
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
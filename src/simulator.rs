@@ -0,0 +1,341 @@
+use crate::action_sequence::flattenActions;
+use crate::cli::Options;
+use crate::diagram::DiagramOptions;
+use crate::flow::Flow;
+use crate::guard_expression::GuardExpression;
+use crate::input_files::readInputFile;
+use crate::machine::{Machine, parseMachines};
+use crate::row::Row;
+use crate::transition_label::shouldBeShown;
+
+use std::io::{BufRead, Write};
+
+
+const MAX_ANONYMOUS_TRANSITIONS: usize = 64;
+const HELP_TEXT: &str = "\
+Commands:
+  <EVENT>  Process the event
+  events   List events handled in the current state
+  state    Print the current state
+  history  List processed events
+  undo     Revert the last processed event
+  help     Print this help
+  quit     End the simulation";
+
+/// Settings of the simulate command.
+pub(crate) struct SimulationOptions
+{
+    pub(crate) machineName: Option<String>,
+    /// Outcomes of guards which are not asked about.
+    pub(crate) guardAnswers: Vec<(String, bool)>
+}
+
+/// Simulates a machine with commands read from the standard input.
+pub(crate) fn simulate(options: &Options, simulationOptions: &SimulationOptions) -> Result<(),String>
+{
+    let fileContent = readInputFile(&options.inputPaths[0])?;
+    let machines = parseMachines(&fileContent, options.nameDisplay)?;
+    let machine = match &simulationOptions.machineName {
+        Some(name) => match machines.iter().find(|machine| machine.name == *name) {
+            Some(machine) => machine,
+            None => return Err(format!("Machine {} was not found.", name))
+        },
+        None => match machines.first() {
+            Some(machine) => machine,
+            None => return Err("Transition table was not found.".into())
+        }
+    };
+    let diagramOptions = options.makeDiagramOptions()?;
+    let simulator = Simulator::new(machine, &diagramOptions, &simulationOptions.guardAnswers, std::io::stdin().lock(),
+        std::io::stdout().lock());
+    simulator.run()
+}
+
+struct Simulator<'a, R: BufRead, W: Write>
+{
+    machine: &'a Machine,
+    options: &'a DiagramOptions,
+    guardAnswers: &'a [(String, bool)],
+    input: R,
+    output: W,
+    currentStates: Vec<String>,
+    history: Vec<Step>
+}
+
+/// A processed event, with the states of all regions before it and descriptions of the taken transitions.
+struct Step
+{
+    event: String,
+    previousStates: Vec<String>,
+    transitions: Vec<String>
+}
+
+impl<'a, R: BufRead, W: Write> Simulator<'a, R, W>
+{
+    fn new(machine: &'a Machine, options: &'a DiagramOptions, guardAnswers: &'a [(String, bool)], input: R, output: W) -> Self
+    {
+        Self{machine, options, guardAnswers, input, output, currentStates: machine.initialStates.clone(), history: vec![]}
+    }
+
+    fn run(mut self) -> Result<(),String>
+    {
+        self.say(&format!("Simulating {}. Type an event name or help to list commands.", self.machine.name))?;
+        for region in 0..self.currentStates.len() {
+            if self.takeAnonymousTransitions(region)?.is_none() {
+                return Ok(());
+            }
+        }
+        self.printCurrentStates()?;
+
+        loop {
+            let command = match self.prompt("> ")? {
+                Some(command) => command,
+                None => return Ok(())
+            };
+            let flow = match command.as_str() {
+                "" => Flow::Continue,
+                "quit" | "exit" => Flow::Break,
+                "help" => self.say(HELP_TEXT).map(|_| Flow::Continue)?,
+                "state" => self.printCurrentStates().map(|_| Flow::Continue)?,
+                "events" => self.printHandledEvents().map(|_| Flow::Continue)?,
+                "history" => self.printHistory().map(|_| Flow::Continue)?,
+                "undo" => self.undo().map(|_| Flow::Continue)?,
+                event => self.processEvent(event)?
+            };
+            if let Flow::Break = flow {
+                return Ok(());
+            }
+        }
+    }
+
+    fn processEvent(&mut self, givenEvent: &str) -> Result<Flow,String>
+    {
+        let namePolicy = &self.options.namePolicy;
+        let event = match self.machine.rows.iter().find(|row| shouldBeShown(&row.event) && namePolicy.isMatch(&row.event, givenEvent)) {
+            Some(row) => row.event.clone(),
+            None => {
+                self.say(&format!("Unknown event: {}, type events to list the handled ones.", givenEvent))?;
+                return Ok(Flow::Continue);
+            }
+        };
+
+        let previousStates = self.currentStates.clone();
+        let mut transitions = vec![];
+        for region in 0..self.currentStates.len() {
+            let state = self.currentStates[region].clone();
+            let candidates: Vec<usize> = (0..self.machine.rows.len())
+                .filter(|&rowIndex| self.machine.rows[rowIndex].start == state && self.machine.rows[rowIndex].event == event)
+                .collect();
+            if candidates.is_empty() {
+                self.say(&format!("No transition from {} on {}.", self.displayName(&state), self.displayName(&event)))?;
+                continue;
+            }
+            self.say(&format!("Candidates from {} on {}:", self.displayName(&state), self.displayName(&event)))?;
+            for &rowIndex in &candidates {
+                self.say(&format!("  row at line {}: {}", self.machine.rows[rowIndex].line, self.describeRow(rowIndex)))?;
+            }
+            match self.takeFirstAllowedTransition(region, &candidates)? {
+                Some(Some(taken)) => transitions.push(taken),
+                Some(None) => self.say("No guard holds, the event is ignored.")?,
+                None => return Ok(Flow::Break)
+            }
+            match self.takeAnonymousTransitions(region)? {
+                Some(taken) => transitions.extend(taken),
+                None => return Ok(Flow::Break)
+            }
+        }
+
+        if !transitions.is_empty() {
+            self.history.push(Step{event: self.displayName(&event), previousStates, transitions});
+        }
+        self.printCurrentStates()?;
+        Ok(Flow::Continue)
+    }
+
+    /// Tries candidate rows from the bottom of the table, as boost::msm does, and takes the first one with a guard that
+    /// holds. Returns `None` when the input ended while asking about a guard.
+    fn takeFirstAllowedTransition(&mut self, region: usize, candidates: &[usize]) -> Result<Option<Option<String>>,String>
+    {
+        for &rowIndex in candidates.iter().rev() {
+            match self.isGuardHolding(rowIndex)? {
+                Some(true) => return Ok(Some(Some(self.takeTransition(region, rowIndex)?))),
+                Some(false) => (),
+                None => return Ok(None)
+            }
+        }
+        Ok(Some(None))
+    }
+
+    /// Takes transitions without an event from the current state of the region, as long as their guards hold.
+    fn takeAnonymousTransitions(&mut self, region: usize) -> Result<Option<Vec<String>>,String>
+    {
+        let mut transitions = vec![];
+        while transitions.len() < MAX_ANONYMOUS_TRANSITIONS {
+            let state = &self.currentStates[region];
+            // An internal transition without an event would stay in the state and be taken again forever.
+            let candidates: Vec<usize> = (0..self.machine.rows.len())
+                .filter(|&rowIndex| {
+                    let row = &self.machine.rows[rowIndex];
+                    row.start == *state && !shouldBeShown(&row.event) && shouldBeShown(&row.target)
+                })
+                .collect();
+            if candidates.is_empty() {
+                return Ok(Some(transitions));
+            }
+            match self.takeFirstAllowedTransition(region, &candidates)? {
+                Some(Some(taken)) => transitions.push(taken),
+                Some(None) => return Ok(Some(transitions)),
+                None => return Ok(None)
+            }
+        }
+        self.say(&format!("Stopped after {} anonymous transitions, they may loop forever.", MAX_ANONYMOUS_TRANSITIONS))?;
+        Ok(Some(transitions))
+    }
+
+    fn takeTransition(&mut self, region: usize, rowIndex: usize) -> Result<String,String>
+    {
+        let row = &self.machine.rows[rowIndex];
+        if let Some(action) = &row.action {
+            for action in flattenActions(action) {
+                self.say(&format!("Executing action: {}", self.options.namePolicy.apply(&action.to_string())))?;
+            }
+        }
+        let description = self.describeTransition(row);
+        self.say(&description)?;
+        // An internal transition only executes its actions and stays in the current state.
+        if shouldBeShown(&row.target) {
+            self.currentStates[region] = row.target.clone();
+        }
+        Ok(description)
+    }
+
+    fn isGuardHolding(&mut self, rowIndex: usize) -> Result<Option<bool>,String>
+    {
+        let guard = match &self.machine.rows[rowIndex].guard {
+            Some(guard) => GuardExpression::new(guard),
+            None => return Ok(Some(true))
+        };
+        let (guardAnswers, namePolicy) = (self.guardAnswers, &self.options.namePolicy);
+        let mut error = None;
+        let result = guard.evaluate(&mut |functor| {
+            let name = functor.to_string();
+            if let Some((_, answer)) = guardAnswers.iter().find(|(answerName, _)| namePolicy.isMatch(&name, answerName)) {
+                return Some(*answer);
+            }
+            let question = format!("Does guard {} hold? [y/n] ", namePolicy.apply(&name));
+            loop {
+                match self.prompt(&question) {
+                    Ok(Some(answer)) => match answer.as_str() {
+                        "y" | "yes" | "true" => return Some(true),
+                        "n" | "no" | "false" => return Some(false),
+                        _ => ()
+                    },
+                    Ok(None) => return None,
+                    Err(e) => {
+                        error = Some(e);
+                        return None;
+                    }
+                }
+            }
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(result)
+        }
+    }
+
+    fn undo(&mut self) -> Result<(),String>
+    {
+        match self.history.pop() {
+            Some(step) => {
+                self.say(&format!("Undone {}.", step.event))?;
+                self.currentStates = step.previousStates;
+                self.printCurrentStates()
+            },
+            None => self.say("Nothing to undo.")
+        }
+    }
+
+    fn printHistory(&mut self) -> Result<(),String>
+    {
+        if self.history.is_empty() {
+            return self.say("No events were processed.");
+        }
+        let lines: Vec<String> = self.history.iter().enumerate()
+            .map(|(index, step)| format!("{}. {}: {}", index + 1, step.event, step.transitions.join(", ")))
+            .collect();
+        self.say(&lines.join("\n"))
+    }
+
+    fn printHandledEvents(&mut self) -> Result<(),String>
+    {
+        let mut events: Vec<String> = vec![];
+        for row in self.machine.rows.iter().filter(|row| self.currentStates.contains(&row.start) && shouldBeShown(&row.event)) {
+            let event = self.displayName(&row.event);
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+        match events.is_empty() {
+            true => self.say("No events are handled in the current state."),
+            false => self.say(&format!("Handled events: {}", events.join(", ")))
+        }
+    }
+
+    fn printCurrentStates(&mut self) -> Result<(),String>
+    {
+        let states: Vec<String> = self.currentStates.iter().map(|state| self.displayName(state)).collect();
+        match states.len() {
+            1 => self.say(&format!("Current state: {}", states[0])),
+            _ => self.say(&format!("Current states: {}", states.join(", ")))
+        }
+    }
+
+    fn describeRow(&self, rowIndex: usize) -> String
+    {
+        let row = &self.machine.rows[rowIndex];
+        let mut description = self.describeTransition(row);
+        if let Some(guard) = &row.guard {
+            description += &format!(" if {}", GuardExpression::new(guard).render(&|functor| self.displayName(&functor.to_string())));
+        }
+        description
+    }
+
+    fn describeTransition(&self, row: &Row) -> String
+    {
+        match shouldBeShown(&row.target) {
+            true => format!("{} --> {}", self.displayName(&row.start), self.displayName(&row.target)),
+            false => format!("{} (internal)", self.displayName(&row.start))
+        }
+    }
+
+    fn displayName(&self, name: &str) -> String
+    {
+        self.options.namePolicy.apply(name)
+    }
+
+    fn say(&mut self, text: &str) -> Result<(),String>
+    {
+        match writeln!(self.output, "{}", text) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("Failed to write output, error: {}", e))
+        }
+    }
+
+    /// Prints the question and reads a trimmed line, returning `None` when the input ended.
+    fn prompt(&mut self, question: &str) -> Result<Option<String>,String>
+    {
+        if let Err(e) = write!(self.output, "{}", question).and_then(|_| self.output.flush()) {
+            return Err(format!("Failed to write output, error: {}", e));
+        }
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) => {
+                self.say("")?;
+                Ok(None)
+            },
+            Ok(_) => Ok(Some(line.trim().to_string())),
+            Err(e) => Err(format!("Failed to read input, error: {}", e))
+        }
+    }
+}
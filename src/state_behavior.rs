@@ -0,0 +1,193 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+
+const KEYWORDS: [&str; 14] = [
+    "if", "for", "while", "switch", "return", "sizeof", "catch", "decltype", "alignof", "noexcept", "typeid",
+    "static_cast", "dynamic_cast", "reinterpret_cast"
+];
+
+static SCOPE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:struct|class)\s+(\w+)[^;{}()]*\{|\bnamespace\s+([\w:]+)\s*\{|\{|\}").unwrap());
+static FUNCTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bon_(entry|exit)\s*\([^)]*\)[^{};]*\{").unwrap());
+static COMMENT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"//[ \t]*([^\n]*)").unwrap());
+static COMMENT_OR_STRING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)//[^\n]*|/\*.*?\*/|"(?:[^"\\]|\\.)*""#).unwrap());
+static CALL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"((?:[A-Za-z_]\w*\s*(?:::|\.|->)\s*)*[A-Za-z_]\w*)\s*(?:<[^;(){}]*>)?\s*\(").unwrap());
+
+/// How much of `on_entry` and `on_exit` of states to show in their boxes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum StateBehaviorDetail
+{
+    Off,
+    Plain,
+    Comment,
+    Calls
+}
+
+impl StateBehaviorDetail
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "off" => Ok(Self::Off),
+            "plain" => Ok(Self::Plain),
+            "comment" => Ok(Self::Comment),
+            "calls" => Ok(Self::Calls),
+            _ => Err(format!("Unknown state behavior detail: {}, expected one of: off, plain, comment, calls.", name))
+        }
+    }
+}
+
+/// Summaries of `on_entry` and `on_exit` defined in the body of a state.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StateBehavior
+{
+    pub(crate) entry: Option<FunctionSummary>,
+    pub(crate) exit: Option<FunctionSummary>
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct FunctionSummary
+{
+    /// The first line of a `//` comment in the body of the function.
+    pub(crate) comment: Option<String>,
+    /// Names of called functions, in the order of their first call.
+    pub(crate) calls: Vec<String>
+}
+
+impl StateBehavior
+{
+    /// Makes lines like `entry / startTimer` to show in the box of the state.
+    pub(crate) fn describe(&self, detail: StateBehaviorDetail) -> Vec<String>
+    {
+        [("entry", &self.entry), ("exit", &self.exit)].into_iter()
+            .filter_map(|(name, function)| {
+                let function = function.as_ref()?;
+                let description = match detail {
+                    StateBehaviorDetail::Off => return None,
+                    StateBehaviorDetail::Plain => None,
+                    StateBehaviorDetail::Comment => function.comment.clone(),
+                    StateBehaviorDetail::Calls => (!function.calls.is_empty()).then(|| function.calls.join("; "))
+                };
+                match description {
+                    Some(description) => Some(format!("{} / {}", name, description)),
+                    None => Some(format!("{} /", name))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Finds structs and classes defining `on_entry` or `on_exit` directly in their bodies, keyed by their names qualified
+/// with enclosing namespaces, structs and classes.
+pub(crate) fn collectStateBehaviors(text: &str) -> HashMap<String, StateBehavior>
+{
+    let mut behaviors = HashMap::new();
+    let mut scopeNames: Vec<Option<&str>> = vec![];
+    for captures in SCOPE_REGEX.captures_iter(text) {
+        let scope = captures.get(0).unwrap();
+        if scope.as_str() == "}" {
+            scopeNames.pop();
+            continue;
+        }
+        scopeNames.push(captures.get(1).or(captures.get(2)).map(|name| name.as_str()));
+        if captures.get(1).is_none() {
+            continue;
+        }
+        let behavior = summarizeBehavior(findBlock(&text[scope.end()..]));
+        if behavior.entry.is_some() || behavior.exit.is_some() {
+            let qualifiedName = scopeNames.iter().flatten().copied().collect::<Vec<_>>().join("::");
+            behaviors.insert(qualifiedName, behavior);
+        }
+    }
+    behaviors
+}
+
+/// Finds the behavior of the state named in a row of the machine, looking the name up in the scopes enclosing the
+/// machine definition, from the innermost one, like the compiler does.
+pub(crate) fn findStateBehavior<'a>(behaviors: &'a HashMap<String, StateBehavior>, qualifiedMachineName: &str,
+    stateName: &str) -> Option<&'a StateBehavior>
+{
+    let stateName = stateName.split('<').next().unwrap_or_default().trim();
+    if let Some(globalName) = stateName.strip_prefix("::") {
+        return behaviors.get(globalName);
+    }
+    let mut scope = qualifiedMachineName;
+    loop {
+        if let Some(behavior) = behaviors.get(&format!("{}::{}", scope, stateName)) {
+            return Some(behavior);
+        }
+        match scope.rfind("::") {
+            Some(index) => scope = &scope[..index],
+            None => return behaviors.get(stateName)
+        }
+    }
+}
+
+/// Summarizes `on_entry` and `on_exit` defined directly in the body of a struct, skipping ones of nested structs.
+fn summarizeBehavior(body: &str) -> StateBehavior
+{
+    let mut behavior = StateBehavior::default();
+    let mut depth = 0;
+    let mut position = 0;
+    for function in FUNCTION_REGEX.captures_iter(body) {
+        let found = function.get(0).unwrap();
+        depth += countDepthChange(&body[position..found.start()]);
+        position = found.start();
+        if depth != 0 {
+            continue;
+        }
+        let summary = summarizeFunction(findBlock(&body[found.end()..]));
+        match &function[1] {
+            "entry" => behavior.entry = Some(summary),
+            _ => behavior.exit = Some(summary)
+        }
+    }
+    behavior
+}
+
+/// Returns the text up to the brace closing the block which has been opened right before it.
+fn findBlock(text: &str) -> &str
+{
+    let mut depth = 0;
+    for (index, character) in text.char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' if depth == 0 => return &text[..index],
+            '}' => depth -= 1,
+            _ => ()
+        }
+    }
+    text
+}
+
+fn countDepthChange(text: &str) -> i32
+{
+    text.chars().map(|character| match character {
+        '{' => 1,
+        '}' => -1,
+        _ => 0
+    }).sum()
+}
+
+fn summarizeFunction(body: &str) -> FunctionSummary
+{
+    let comment = COMMENT_REGEX.captures_iter(body)
+        .map(|captures| captures[1].trim().to_string())
+        .find(|comment| !comment.is_empty());
+
+    let code = COMMENT_OR_STRING_REGEX.replace_all(body, " ");
+    let mut calls: Vec<String> = vec![];
+    for captures in CALL_REGEX.captures_iter(&code) {
+        let call: String = captures[1].split_whitespace().collect();
+        let name = call.rsplit(['.', ':', '>']).next().unwrap_or_default();
+        if !KEYWORDS.contains(&name) && !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+    FunctionSummary{comment, calls}
+}
@@ -0,0 +1,315 @@
+use crate::diagram::{Diagram, DiagramTransition};
+use crate::dot_renderer::{convertDotToSvg, renderDot};
+
+use std::collections::{HashMap, VecDeque};
+
+
+const CHARACTER_WIDTH: f64 = 7.2;
+const LINE_HEIGHT: f64 = 16.0;
+const STATE_PADDING: f64 = 8.0;
+const STATE_GAP: f64 = 60.0;
+const LAYER_GAP: f64 = 90.0;
+const MARGIN: f64 = 20.0;
+const INITIAL_STATE_RADIUS: f64 = 6.0;
+const BEND: f64 = 40.0;
+const PARALLEL_TRANSITION_GAP: f64 = 24.0;
+const DEFAULT_COLOR: &str = "black";
+
+/// Program laying out diagrams as SVG.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SvgRenderer
+{
+    BuiltIn,
+    Dot
+}
+
+impl SvgRenderer
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "builtin" => Ok(Self::BuiltIn),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!("Unknown SVG renderer: {}, expected one of: builtin, dot.", name))
+        }
+    }
+}
+
+/// Renders the diagram as an SVG element, without the XML declaration, so that it can be embedded in HTML. Identifiers
+/// of elements start with `idPrefix`, so that they stay unique when several diagrams are embedded in one page.
+pub(crate) fn renderSvg(diagram: &Diagram, renderer: SvgRenderer, idPrefix: &str) -> Result<String,String>
+{
+    match renderer {
+        SvgRenderer::BuiltIn => Ok(renderBuiltInSvg(diagram, idPrefix)),
+        SvgRenderer::Dot => {
+            let svg = convertDotToSvg(&renderDot(diagram))?;
+            match svg.find("<svg") {
+                Some(index) => Ok(svg[index..].trim_end().into()),
+                None => Err("Command dot did not produce SVG.".into())
+            }
+        }
+    }
+}
+
+/// A box of a state placed in the diagram, with its top left corner.
+struct Placement
+{
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64
+}
+
+impl Placement
+{
+    fn center(&self) -> (f64, f64)
+    {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Finds the point where a line from the center towards `point` leaves the box.
+    fn findBorderPoint(&self, point: (f64, f64)) -> (f64, f64)
+    {
+        let (centerX, centerY) = self.center();
+        let (dx, dy) = (point.0 - centerX, point.1 - centerY);
+        if dx == 0.0 && dy == 0.0 {
+            return (centerX, centerY);
+        }
+        let scale = f64::min(
+            if dx == 0.0 { f64::INFINITY } else { self.width / 2.0 / dx.abs() },
+            if dy == 0.0 { f64::INFINITY } else { self.height / 2.0 / dy.abs() });
+        (centerX + dx * scale, centerY + dy * scale)
+    }
+}
+
+/// Lays states out in layers by their distance from the initial states, drawing transitions to lower layers as straight
+/// lines and the other ones as curves.
+fn renderBuiltInSvg(diagram: &Diagram, idPrefix: &str) -> String
+{
+    let layers = assignLayers(diagram);
+    let mut placements: HashMap<&str, Placement> = HashMap::new();
+    let mut y = MARGIN + INITIAL_STATE_RADIUS * 2.0 + LAYER_GAP / 2.0;
+    let mut layerWidths = vec![];
+    for layer in &layers {
+        let mut x = MARGIN;
+        let mut layerHeight: f64 = 0.0;
+        for &index in layer {
+            let state = &diagram.states[index];
+            let lineCount = 1 + state.descriptions.len();
+            let width = measureText([&state.label].into_iter().chain(&state.descriptions)) + STATE_PADDING * 2.0;
+            let height = lineCount as f64 * LINE_HEIGHT + STATE_PADDING * 2.0;
+            placements.insert(&state.id, Placement{x, y, width, height});
+            x += width + STATE_GAP;
+            layerHeight = layerHeight.max(height);
+        }
+        layerWidths.push(x - STATE_GAP - MARGIN);
+        y += layerHeight + LAYER_GAP;
+    }
+    let diagramWidth = layerWidths.iter().copied().fold(0.0, f64::max);
+    for (layer, layerWidth) in layers.iter().zip(&layerWidths) {
+        for &index in layer {
+            placements.get_mut(diagram.states[index].id.as_str()).unwrap().x += (diagramWidth - layerWidth) / 2.0;
+        }
+    }
+
+    let mut canvas = Canvas::new(idPrefix);
+    for initialState in &diagram.initialStates {
+        if let Some(placement) = placements.get(initialState.as_str()) {
+            let (centerX, _) = placement.center();
+            let circleY = placement.y - LAYER_GAP / 2.0;
+            canvas.add(centerX + INITIAL_STATE_RADIUS, circleY + INITIAL_STATE_RADIUS, format!(
+                r#"<circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}"/>"#, centerX, circleY, INITIAL_STATE_RADIUS, DEFAULT_COLOR));
+            canvas.addArrow(&format!("M {:.1} {:.1} L {:.1} {:.1}", centerX, circleY + INITIAL_STATE_RADIUS, centerX, placement.y),
+                DEFAULT_COLOR);
+        }
+    }
+    for state in &diagram.states {
+        let placement = &placements[state.id.as_str()];
+        let (color, strokeWidth) = match &state.color {
+            Some(color) => (color.as_str(), 2),
+            None => (DEFAULT_COLOR, 1)
+        };
+        canvas.add(placement.x + placement.width, placement.y + placement.height, format!(
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="8" fill="white" stroke="{}" stroke-width="{}"/>"#,
+            placement.x, placement.y, placement.width, placement.height, escape(color), strokeWidth));
+        let (centerX, _) = placement.center();
+        canvas.addText(centerX, placement.y + STATE_PADDING, "middle", &[&state.label]);
+        if !state.descriptions.is_empty() {
+            let separatorY = placement.y + STATE_PADDING + LINE_HEIGHT + 2.0;
+            canvas.add(0.0, 0.0, format!(r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"/>"#,
+                placement.x, separatorY, placement.x + placement.width, separatorY, escape(color)));
+            let descriptions: Vec<&str> = state.descriptions.iter().map(String::as_str).collect();
+            canvas.addText(placement.x + STATE_PADDING, separatorY + 2.0, "start", &descriptions);
+        }
+    }
+    let mut drawnPairs: HashMap<(&str, &str), usize> = HashMap::new();
+    for transition in &diagram.transitions {
+        let index = drawnPairs.entry((&transition.start, &transition.target)).or_default();
+        drawTransition(&mut canvas, transition, &placements[transition.start.as_str()],
+            &placements[transition.target.as_str()], *index);
+        *index += 1;
+    }
+    canvas.render(&diagram.name)
+}
+
+/// Groups indexes of states into layers: initial states first, then states reachable from them in one transition and so
+/// on. Unreachable states start further layers in the order of their appearance.
+fn assignLayers(diagram: &Diagram) -> Vec<Vec<usize>>
+{
+    let indexes: HashMap<&str, usize> = diagram.states.iter().enumerate().map(|(index, state)| (state.id.as_str(), index)).collect();
+    let mut stateLayers: Vec<Option<usize>> = vec![None; diagram.states.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for initialState in &diagram.initialStates {
+        if let Some(&index) = indexes.get(initialState.as_str()) && stateLayers[index].is_none() {
+            stateLayers[index] = Some(0);
+            queue.push_back(index);
+        }
+    }
+    let mut nextLayer = 0;
+    loop {
+        while let Some(index) = queue.pop_front() {
+            let layer = stateLayers[index].unwrap();
+            nextLayer = nextLayer.max(layer + 1);
+            for transition in diagram.transitions.iter().filter(|transition| transition.start == diagram.states[index].id) {
+                let targetIndex = indexes[transition.target.as_str()];
+                if stateLayers[targetIndex].is_none() {
+                    stateLayers[targetIndex] = Some(layer + 1);
+                    queue.push_back(targetIndex);
+                }
+            }
+        }
+        match stateLayers.iter().position(Option::is_none) {
+            Some(index) => {
+                stateLayers[index] = Some(nextLayer);
+                queue.push_back(index);
+            },
+            None => break
+        }
+    }
+    let mut layers = vec![vec![]; nextLayer];
+    for (index, layer) in stateLayers.into_iter().enumerate() {
+        layers[layer.unwrap()].push(index);
+    }
+    layers
+}
+
+fn drawTransition(canvas: &mut Canvas, transition: &DiagramTransition, start: &Placement, target: &Placement, parallelIndex: usize)
+{
+    let color = transition.color.as_deref().unwrap_or(DEFAULT_COLOR);
+    let labelLines: Vec<&str> = transition.labelLines.iter().map(String::as_str)
+        .chain(transition.note.iter().flat_map(|note| note.lines()))
+        .collect();
+    let offset = parallelIndex as f64 * PARALLEL_TRANSITION_GAP;
+    if transition.start == transition.target {
+        let (right, top, bottom) = (start.x + start.width, start.y + start.height / 4.0, start.y + start.height * 3.0 / 4.0);
+        let reach = BEND + offset;
+        canvas.addArrow(&format!("M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}",
+            right, top, right + reach, top - reach / 2.0, right + reach, bottom + reach / 2.0, right, bottom), color);
+        let labelY = start.y + start.height / 2.0 - labelLines.len() as f64 * LINE_HEIGHT / 2.0;
+        canvas.addText(right + reach * 0.75 + 4.0, labelY, "start", &labelLines);
+        return;
+    }
+
+    let (startCenter, targetCenter) = (start.center(), target.center());
+    let isDownward = target.y > start.y + start.height;
+    let middle = ((startCenter.0 + targetCenter.0) / 2.0, (startCenter.1 + targetCenter.1) / 2.0);
+    let (dx, dy) = (targetCenter.0 - startCenter.0, targetCenter.1 - startCenter.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    // Longer curves bend more to go around states of the layers they cross.
+    let bend = match isDownward {
+        true => offset,
+        false => BEND.max(length / 4.0) + offset
+    };
+    let control = (middle.0 - dy / length * bend, middle.1 + dx / length * bend);
+    let from = start.findBorderPoint(control);
+    let to = target.findBorderPoint(control);
+    canvas.addArrow(&format!("M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}", from.0, from.1, control.0, control.1, to.0, to.1), color);
+    let labelPoint = ((from.0 + 2.0 * control.0 + to.0) / 4.0, (from.1 + 2.0 * control.1 + to.1) / 4.0);
+    canvas.addText(labelPoint.0 + 4.0, labelPoint.1 - labelLines.len() as f64 * LINE_HEIGHT / 2.0, "start", &labelLines);
+}
+
+/// Collects elements of the drawing and the extent they cover.
+struct Canvas<'a>
+{
+    idPrefix: &'a str,
+    elements: Vec<String>,
+    arrowColors: Vec<String>,
+    right: f64,
+    bottom: f64
+}
+
+impl<'a> Canvas<'a>
+{
+    fn new(idPrefix: &'a str) -> Self
+    {
+        Self{idPrefix, elements: vec![], arrowColors: vec![], right: 0.0, bottom: 0.0}
+    }
+
+    fn add(&mut self, right: f64, bottom: f64, element: String)
+    {
+        self.right = self.right.max(right);
+        self.bottom = self.bottom.max(bottom);
+        self.elements.push(element);
+    }
+
+    fn addArrow(&mut self, path: &str, color: &str)
+    {
+        let markerIndex = match self.arrowColors.iter().position(|arrowColor| arrowColor == color) {
+            Some(index) => index,
+            None => {
+                self.arrowColors.push(color.into());
+                self.arrowColors.len() - 1
+            }
+        };
+        self.elements.push(format!(r#"<path d="{}" fill="none" stroke="{}" marker-end="url(#{}-arrow{})"/>"#,
+            path, escape(color), escape(self.idPrefix), markerIndex));
+    }
+
+    /// Adds lines of text with the top of the first one at `y`.
+    fn addText(&mut self, x: f64, y: f64, anchor: &str, lines: &[&str])
+    {
+        if lines.is_empty() {
+            return;
+        }
+        let width = measureText(lines);
+        let right = match anchor {
+            "middle" => x + width / 2.0,
+            _ => x + width
+        };
+        let spans: Vec<String> = lines.iter().enumerate()
+            .map(|(index, line)| format!(r#"<tspan x="{:.1}" dy="{}">{}</tspan>"#, x, if index == 0 { 0.0 } else { LINE_HEIGHT }, escape(line)))
+            .collect();
+        self.add(right, y + lines.len() as f64 * LINE_HEIGHT, format!(r#"<text x="{:.1}" y="{:.1}" text-anchor="{}">{}</text>"#,
+            x, y + LINE_HEIGHT - 4.0, anchor, spans.concat()));
+    }
+
+    fn render(self, title: &str) -> String
+    {
+        let (width, height) = ((self.right + MARGIN).ceil(), (self.bottom + MARGIN).ceil());
+        let mut lines = vec![
+            format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="monospace" font-size="12">"#,
+                width, height, width, height),
+            format!("<title>{}</title>", escape(title)),
+            "<defs>".into()
+        ];
+        for (index, color) in self.arrowColors.iter().enumerate() {
+            lines.push(format!(
+                r#"<marker id="{}-arrow{}" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker>"#,
+                escape(self.idPrefix), index, escape(color)));
+        }
+        lines.push("</defs>".into());
+        lines.extend(self.elements);
+        lines.push("</svg>".into());
+        lines.join("\n")
+    }
+}
+
+fn measureText<T: AsRef<str>>(lines: impl IntoIterator<Item = T>) -> f64
+{
+    lines.into_iter().map(|line| line.as_ref().chars().count()).max().unwrap_or_default() as f64 * CHARACTER_WIDTH
+}
+
+fn escape(text: &str) -> String
+{
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,285 @@
+use crate::type_expression::TypeExpression;
+use crate::type_expression_parser::parseTypeExpression;
+
+use regex::Regex;
+use std::collections::HashMap;
+
+
+const MAX_RESOLUTION_DEPTH: usize = 16;
+
+pub(crate) struct SymbolTable
+{
+    typeAliases: HashMap<String, TypeAlias>,
+    namespaceAliases: HashMap<String, String>,
+    identifierRegex: Regex
+}
+
+struct TypeAlias
+{
+    target: String,
+    templateParameters: Option<Vec<TemplateParameter>>
+}
+
+struct TemplateParameter
+{
+    name: String,
+    defaultValue: Option<String>,
+    isVariadic: bool
+}
+
+impl SymbolTable
+{
+    /// Collects aliases visible in the scope, given as a name qualified with namespaces, structs and classes, like the
+    /// one of a machine definition. Aliases of enclosing scopes are visible in it, and inner ones hide outer ones.
+    pub(crate) fn collect(text: &str, scope: &str) -> Self
+    {
+        let text = stripComments(text);
+        let scopes = ScopeMap::new(&text);
+        let mut symbols = Self{
+            typeAliases: HashMap::new(),
+            namespaceAliases: HashMap::new(),
+            identifierRegex: Regex::new(r"(?:::)?[A-Za-z_]\w*(?:\s*::\s*[A-Za-z_]\w*)*").unwrap()
+        };
+        let mut typeAliasDepths: HashMap<String, usize> = HashMap::new();
+        let mut namespaceAliasDepths: HashMap<String, usize> = HashMap::new();
+
+        let namespaceAliasRegex = Regex::new(r"\bnamespace\s+(\w+)\s*=\s*((?:::)?[\w:\s]+?)\s*;").unwrap();
+        for captures in namespaceAliasRegex.captures_iter(&text) {
+            if let Some(depth) = scopes.findVisibleDepth(captures.get(0).unwrap().start(), scope)
+                && isInnermost(&mut namespaceAliasDepths, &captures[1], depth) {
+                symbols.namespaceAliases.insert(captures[1].into(), normalizeWhitespace(&captures[2]));
+            }
+        }
+
+        let usingRegex = Regex::new(r"(?:\btemplate\s*<([^;{}]*?)>\s*)?\busing\s+(\w+)\s*=\s*([^;{}]+);").unwrap();
+        for captures in usingRegex.captures_iter(&text) {
+            if let Some(depth) = scopes.findVisibleDepth(captures.get(0).unwrap().start(), scope)
+                && isInnermost(&mut typeAliasDepths, &captures[2], depth) {
+                let templateParameters = captures.get(1).map(|parameters| parseTemplateParameters(parameters.as_str()));
+                symbols.typeAliases.insert(captures[2].into(), TypeAlias{target: normalizeWhitespace(&captures[3]), templateParameters});
+            }
+        }
+
+        let typedefRegex = Regex::new(r"\btypedef\s+([^;{}]+?)\s*\b(\w+)\s*;").unwrap();
+        for captures in typedefRegex.captures_iter(&text) {
+            if let Some(depth) = scopes.findVisibleDepth(captures.get(0).unwrap().start(), scope)
+                && isInnermost(&mut typeAliasDepths, &captures[2], depth) {
+                symbols.typeAliases.insert(captures[2].into(), TypeAlias{target: normalizeWhitespace(&captures[1]), templateParameters: None});
+            }
+        }
+
+        symbols
+    }
+
+    /// Returns the aliased type if `name` is a plain (non-template) alias of a row, e.g. `typedef msmf::Row<A, E, B> T1;`.
+    pub(crate) fn findRowAlias(&self, name: &str, rowRegex: &Regex) -> Option<&str>
+    {
+        let alias = self.typeAliases.get(name)?;
+        if alias.templateParameters.is_some() {
+            return None;
+        }
+        let (templateName, _) = alias.target.split_once('<')?;
+        match rowRegex.is_match(templateName.trim()) {
+            true => Some(&alias.target),
+            false => None
+        }
+    }
+
+    /// Substitutes template arguments into an alias template, e.g. `Not<IsEmpty>` declared with
+    /// `template <class T> using Not = euml::Not_<T>;` becomes `euml::Not_<IsEmpty>`.
+    pub(crate) fn expandTemplateAlias(&self, name: &str, arguments: &[TypeExpression]) -> Option<TypeExpression>
+    {
+        let alias = self.typeAliases.get(name)?;
+        let parameters = alias.templateParameters.as_ref()?;
+        let target = parseTypeExpression(&alias.target).ok()?;
+
+        let mut boundArguments = HashMap::new();
+        for (index, parameter) in parameters.iter().enumerate() {
+            let boundArgument = match (parameter.isVariadic, arguments.get(index), &parameter.defaultValue) {
+                (true, _, _) => arguments.get(index..).unwrap_or_default().to_vec(),
+                (false, Some(argument), _) => vec![argument.clone()],
+                (false, None, Some(defaultValue)) => vec![parseTypeExpression(defaultValue).ok()?],
+                (false, None, None) => return None
+            };
+            boundArguments.insert(parameter.name.as_str(), (parameter.isVariadic, boundArgument));
+        }
+        if arguments.len() > parameters.len() && !parameters.iter().any(|parameter| parameter.isVariadic) {
+            return None;
+        }
+        Some(substitute(&target, &boundArguments))
+    }
+
+    /// Resolves aliases in every name found in `text`, leaving the rest of it untouched.
+    pub(crate) fn canonicalizeText(&self, text: &str) -> String
+    {
+        let mut output = text.to_string();
+        for _ in 0..MAX_RESOLUTION_DEPTH {
+            let resolved = self.identifierRegex.replace_all(&output, |captures: &regex::Captures| self.resolveOnce(&captures[0]))
+                .into_owned();
+            if resolved == output {
+                break;
+            }
+            output = resolved;
+        }
+        output
+    }
+
+    fn resolveOnce(&self, name: &str) -> String
+    {
+        let name = normalizeWhitespace(name).replace(" ", "");
+        if let Some(alias) = self.typeAliases.get(&name)
+            && self.isRenamingAlias(alias) {
+            return match &alias.templateParameters {
+                Some(_) => alias.target[..alias.target.find('<').unwrap()].trim().to_string(),
+                None => alias.target.clone()
+            };
+        }
+
+        let (firstSegment, rest) = match name.split_once("::") {
+            Some(parts) => parts,
+            None => return name
+        };
+        if let Some(namespace) = self.namespaceAliases.get(firstSegment) {
+            return format!("{}::{}", namespace, rest);
+        }
+        match self.typeAliases.get(firstSegment) {
+            Some(alias) if alias.templateParameters.is_none() && isQualifiedName(&alias.target) =>
+                format!("{}::{}", alias.target, rest),
+            _ => name
+        }
+    }
+
+    fn isRenamingAlias(&self, alias: &TypeAlias) -> bool
+    {
+        let parameters = match &alias.templateParameters {
+            Some(parameters) => parameters,
+            None => return true
+        };
+        let (templateName, arguments) = match alias.target.split_once('<') {
+            Some(parts) => parts,
+            None => return false
+        };
+        let arguments: Vec<String> = match arguments.strip_suffix('>') {
+            Some(arguments) if !arguments.contains('<') =>
+                arguments.split(',').map(|argument| argument.trim().to_string()).filter(|argument| !argument.is_empty()).collect(),
+            _ => return false
+        };
+        let parameterNames: Vec<&String> = parameters.iter().map(|parameter| &parameter.name).collect();
+        isQualifiedName(templateName.trim()) && arguments.iter().collect::<Vec<_>>() == parameterNames
+            && !parameters.iter().any(|parameter| parameter.isVariadic)
+    }
+}
+
+/// Names of the scopes opened by namespaces, structs and classes, known at the positions where they change.
+struct ScopeMap
+{
+    /// Positions in the text, in increasing order, with the qualified name of the scope starting at each of them.
+    changes: Vec<(usize, String)>
+}
+
+impl ScopeMap
+{
+    fn new(text: &str) -> Self
+    {
+        let scopeRegex = Regex::new(r"\b(?:struct|class)\s+(\w+)[^;{}()]*\{|\bnamespace\s+([\w:]+)\s*\{|\{|\}").unwrap();
+        let mut names: Vec<Option<&str>> = vec![];
+        let mut changes = vec![(0, String::new())];
+        for captures in scopeRegex.captures_iter(text) {
+            let scope = captures.get(0).unwrap();
+            match scope.as_str() {
+                "}" => { names.pop(); },
+                _ => names.push(captures.get(1).or(captures.get(2)).map(|name| name.as_str()))
+            }
+            changes.push((scope.end(), names.iter().flatten().copied().collect::<Vec<_>>().join("::")));
+        }
+        Self{changes}
+    }
+
+    /// Returns the number of named scopes enclosing the position when a declaration placed there is visible in the
+    /// given scope, that is when it is placed in the scope or in one enclosing it.
+    fn findVisibleDepth(&self, position: usize, visibleScope: &str) -> Option<usize>
+    {
+        let index = self.changes.partition_point(|(start, _)| *start <= position) - 1;
+        let scope = &self.changes[index].1;
+        if scope.is_empty() {
+            return Some(0);
+        }
+        let isEnclosing = visibleScope == scope
+            || visibleScope.strip_prefix(scope.as_str()).is_some_and(|rest| rest.starts_with("::"));
+        isEnclosing.then(|| scope.matches("::").count() + 1)
+    }
+}
+
+/// Tells whether an alias declared at the depth hides the ones of the same name found so far, remembering its depth.
+fn isInnermost(depths: &mut HashMap<String, usize>, name: &str, depth: usize) -> bool
+{
+    match depths.get(name) {
+        Some(&knownDepth) if knownDepth > depth => false,
+        _ => {
+            depths.insert(name.into(), depth);
+            true
+        }
+    }
+}
+
+fn stripComments(text: &str) -> String
+{
+    let commentRegex = Regex::new(r"(?s)//[^\n]*|/\*.*?\*/").unwrap();
+    commentRegex.replace_all(text, " ").into_owned()
+}
+
+fn normalizeWhitespace(text: &str) -> String
+{
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn isQualifiedName(text: &str) -> bool
+{
+    !text.is_empty() && text.chars().all(|character| character.is_alphanumeric() || character == '_' || character == ':')
+}
+
+fn parseTemplateParameters(parameters: &str) -> Vec<TemplateParameter>
+{
+    parameters.split(',')
+        .filter_map(|parameter| {
+            let (declaration, defaultValue) = match parameter.split_once('=') {
+                Some((declaration, defaultValue)) => (declaration.trim(), Some(defaultValue.trim().to_string())),
+                None => (parameter.trim(), None)
+            };
+            let name = declaration.rsplit(|character: char| character.is_whitespace() || character == '.').next()?;
+            match name.is_empty() {
+                true => None,
+                false => Some(TemplateParameter{name: name.into(), defaultValue, isVariadic: declaration.contains("...")})
+            }
+        })
+        .collect()
+}
+
+type BoundArguments<'a> = HashMap<&'a str, (bool, Vec<TypeExpression>)>;
+
+fn substitute(expression: &TypeExpression, boundArguments: &BoundArguments) -> TypeExpression
+{
+    match expression {
+        TypeExpression::Name(name) => match boundArguments.get(name.as_str()) {
+            Some((false, arguments)) => arguments[0].clone(),
+            _ => expression.clone()
+        },
+        TypeExpression::Template{name, arguments} => {
+            let mut substitutedArguments = vec![];
+            for argument in arguments {
+                match argument {
+                    TypeExpression::Name(argumentName) => match argumentName.strip_suffix("...").and_then(|name| boundArguments.get(name)) {
+                        Some((true, variadicArguments)) => substitutedArguments.extend(variadicArguments.iter().cloned()),
+                        _ => substitutedArguments.push(substitute(argument, boundArguments))
+                    },
+                    _ => substitutedArguments.push(substitute(argument, boundArguments))
+                }
+            }
+            TypeExpression::Template{name: name.clone(), arguments: substitutedArguments}
+        },
+        TypeExpression::Number(_) => expression.clone(),
+        TypeExpression::Member{scope, name} =>
+            TypeExpression::Member{scope: Box::new(substitute(scope, boundArguments)), name: name.clone()},
+        TypeExpression::Address(inner) => TypeExpression::Address(Box::new(substitute(inner, boundArguments)))
+    }
+}
@@ -0,0 +1,222 @@
+use crate::action_sequence::flattenActions;
+use crate::cli::{Options, isStdin};
+use crate::diagram::makeIdentifier;
+use crate::guard_expression::GuardExpression;
+use crate::input_files::readInputFile;
+use crate::machine::{Machine, parseMachines};
+use crate::name_resolver::NameDisplay;
+use crate::paths::{Path, findShortestPath};
+use crate::row::Row;
+use crate::transition_label::shouldBeShown;
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TestFramework
+{
+    GoogleTest,
+    Catch2
+}
+
+impl TestFramework
+{
+    pub(crate) fn fromName(name: &str) -> Result<Self,String>
+    {
+        match name {
+            "gtest" => Ok(Self::GoogleTest),
+            "catch2" => Ok(Self::Catch2),
+            _ => Err(format!("Unknown test framework: {}, expected one of: gtest, catch2.", name))
+        }
+    }
+
+    fn header(self) -> &'static str
+    {
+        match self {
+            Self::GoogleTest => "<gtest/gtest.h>",
+            Self::Catch2 => "<catch2/catch_test_macros.hpp>"
+        }
+    }
+}
+
+const IS_IN_STATE_FUNCTION: &str = "\
+template <class State, class Machine>
+bool isInState(const Machine& machine)
+{
+    const int stateId = boost::msm::back::get_state_id<typename Machine::stt, State>::value;
+    for (int region = 0; region < Machine::nr_regions::value; ++region) {
+        if (machine.current_state()[region] == stateId) {
+            return true;
+        }
+    }
+    return false;
+}
+";
+
+/// Generates a C++ test per transition, which drives the machine to the start state of the row along the shortest path,
+/// processes the event of the row and checks that the machine is in the target state. Names are canonical, because
+/// aliases may be declared inside the machine definition and be unavailable to the tests. Before events of guarded rows,
+/// the tests call generated hooks, which are to be filled in to make the guards hold.
+pub(crate) fn generateTests(options: &Options, framework: TestFramework) -> Result<String,String>
+{
+    let inputPath = &options.inputPaths[0];
+    let fileContent = readInputFile(inputPath)?;
+    let machines = parseMachines(&fileContent, NameDisplay::Canonical)?;
+    if machines.is_empty() {
+        return Err("Transition table was not found.".into());
+    }
+
+    let mut output = String::from("// Transition coverage tests generated by boost-msm-grapher.\n");
+    writeln!(output, "#include {}", framework.header()).unwrap();
+    output += "#include <boost/msm/back/state_machine.hpp>\n";
+    match isStdin(inputPath) {
+        true => output += "// TODO: include the header defining the state machines.\n",
+        false => {
+            let fileName = inputPath.file_name().unwrap_or(inputPath.as_os_str()).to_string_lossy();
+            writeln!(output, "#include \"{}\"", fileName).unwrap();
+        }
+    }
+    output += "\nnamespace\n{\n";
+    output += IS_IN_STATE_FUNCTION;
+    output += "}\n";
+    let mut usedIdentifiers = HashSet::new();
+    for machine in &machines {
+        let identifier = makeUniqueIdentifier(&machine.qualifiedName, &mut usedIdentifiers);
+        // Tests are placed in the namespace of the machine, so that names of states and events are found like in rows.
+        if let Some(namespace) = &machine.namespace {
+            writeln!(output, "\nnamespace {}\n{{", namespace).unwrap();
+        }
+        writeln!(output, "\nusing {}Machine = boost::msm::back::state_machine<{}>;", identifier, machine.qualifiedName)
+            .unwrap();
+        let paths: Vec<Option<Path>> = machine.rows.iter().map(|row| findPathToState(machine, &row.start)).collect();
+        output += &generateGuardHooks(machine, &identifier, &paths);
+        for (rowIndex, (row, path)) in machine.rows.iter().zip(&paths).enumerate() {
+            output += "\n";
+            output += &generateTest(machine, &identifier, rowIndex, row, path.as_ref(), framework);
+        }
+        if let Some(namespace) = &machine.namespace {
+            writeln!(output, "}} // namespace {}", namespace).unwrap();
+        }
+    }
+    Ok(output)
+}
+
+/// Generates a function per guarded row taken by the tests, called before processing the event of the row.
+fn generateGuardHooks(machine: &Machine, identifier: &str, paths: &[Option<Path>]) -> String
+{
+    let mut takenRowIndexes = BTreeSet::new();
+    for (rowIndex, path) in paths.iter().enumerate() {
+        if let Some(path) = path {
+            takenRowIndexes.extend(path.iter().copied().chain([rowIndex]));
+        }
+    }
+    let mut hooks = String::new();
+    for rowIndex in takenRowIndexes {
+        if let Some(guard) = &machine.rows[rowIndex].guard {
+            let guard = GuardExpression::new(guard).render(&|functor| functor.to_string());
+            let hookName = makeGuardHookName(identifier, rowIndex);
+            writeln!(hooks, "\n// Called before processing the event of row {}, to make guard {} hold.", rowIndex + 1, guard)
+                .unwrap();
+            writeln!(hooks, "void {}([[maybe_unused]] {}Machine& machine)", hookName, identifier).unwrap();
+            hooks += "{\n    // TODO: set up the machine so that the guard holds.\n}\n";
+        }
+    }
+    match hooks.is_empty() {
+        true => hooks,
+        false => format!("\nnamespace\n{{{}}}\n", hooks)
+    }
+}
+
+fn generateTest(machine: &Machine, identifier: &str, rowIndex: usize, row: &Row, path: Option<&Path>,
+    framework: TestFramework) -> String
+{
+    // Names of test cases of Catch2 have to be unique, so machines of the same name are told apart by their identifiers.
+    let machineName = match makeIdentifier(&machine.qualifiedName) == identifier {
+        true => &machine.qualifiedName,
+        false => identifier
+    };
+    // An internal transition stays in its start state.
+    let target = match shouldBeShown(&row.target) {
+        true => &row.target,
+        false => &row.start
+    };
+    let mut test = match framework {
+        TestFramework::GoogleTest => format!("TEST({}Transitions, Row{}_{}_{}_{})\n{{\n", identifier,
+            rowIndex + 1, makeIdentifier(&row.start), makeIdentifier(&row.event), makeIdentifier(target)),
+        TestFramework::Catch2 => format!("TEST_CASE(\"{}: row {}: {} --{}--> {}\", \"[{}]\")\n{{\n", machineName,
+            rowIndex + 1, row.start, row.event, target, machineName)
+    };
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let reason = format!("State {} is unreachable from the initial state.", row.start);
+            match framework {
+                TestFramework::GoogleTest => writeln!(test, "    GTEST_SKIP() << \"{}\";", reason).unwrap(),
+                TestFramework::Catch2 => writeln!(test, "    SKIP(\"{}\");", reason).unwrap()
+            }
+            return test + "}\n";
+        }
+    };
+
+    writeln!(test, "    {}Machine machine;", identifier).unwrap();
+    test += "    machine.start();\n";
+    for &pathRowIndex in path {
+        test += &generateStep(identifier, pathRowIndex, &machine.rows[pathRowIndex]);
+    }
+    test += &generateStep(identifier, rowIndex, row);
+    match framework {
+        TestFramework::GoogleTest => writeln!(test, "    EXPECT_TRUE(isInState<{}>(machine));", target).unwrap(),
+        TestFramework::Catch2 => writeln!(test, "    CHECK(isInState<{}>(machine));", target).unwrap()
+    }
+    test + "}\n"
+}
+
+fn generateStep(identifier: &str, rowIndex: usize, row: &Row) -> String
+{
+    let mut step = String::new();
+    if row.guard.is_some() {
+        writeln!(step, "    {}(machine);", makeGuardHookName(identifier, rowIndex)).unwrap();
+    }
+    match shouldBeShown(&row.event) {
+        true => writeln!(step, "    machine.process_event({}{{}});", row.event).unwrap(),
+        false => writeln!(step, "    // The anonymous transition from {} to {} is taken automatically.", row.start, row.target).unwrap()
+    }
+    if let Some(action) = &row.action {
+        for action in flattenActions(action) {
+            writeln!(step, "    // TODO: check effects of action {}.", action).unwrap();
+        }
+    }
+    step
+}
+
+/// Finds the shortest path from any of the initial states, which is empty when the state is initial.
+fn findPathToState(machine: &Machine, state: &str) -> Option<Path>
+{
+    if machine.initialStates.iter().any(|initialState| initialState == state) {
+        return Some(vec![]);
+    }
+    machine.initialStates.iter()
+        .filter_map(|initialState| findShortestPath(machine, initialState, state))
+        .min_by_key(|path| path.len())
+}
+
+/// Makes an identifier of the machine for names of its alias, hooks and tests, numbered when machines of the same
+/// name were already given one.
+fn makeUniqueIdentifier(qualifiedName: &str, usedIdentifiers: &mut HashSet<String>) -> String
+{
+    let baseIdentifier = makeIdentifier(qualifiedName);
+    let mut identifier = baseIdentifier.clone();
+    let mut index = 2;
+    while usedIdentifiers.contains(&identifier) {
+        identifier = format!("{}_{}", baseIdentifier, index);
+        index += 1;
+    }
+    usedIdentifiers.insert(identifier.clone());
+    identifier
+}
+
+fn makeGuardHookName(identifier: &str, rowIndex: usize) -> String
+{
+    format!("makeGuardHold_{}_Row{}", identifier, rowIndex + 1)
+}
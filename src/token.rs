@@ -7,7 +7,7 @@ pub(crate) enum Token
     Comma,
     Identifier(String),
     InstructionEnd,
-    Keyword(String),
+    Keyword,
     TemplateStart,
     TemplateEnd
 }
@@ -22,7 +22,7 @@ impl Token
             ";" => Token::InstructionEnd,
             "<" => Token::TemplateStart,
             ">" => Token::TemplateEnd,
-            "struct" => Token::Keyword(text.into()),
+            "struct" => Token::Keyword,
             "{" => Token::BlockStart,
             "}" => Token::BlockEnd,
             _ => Token::Identifier(text.into())
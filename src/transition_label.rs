@@ -0,0 +1,74 @@
+use crate::action_sequence::{ActionListLayout, flattenActions};
+use crate::diagram::DiagramOptions;
+use crate::guard_expression::GuardExpression;
+use crate::row::Row;
+
+
+/// Displayed parts of a transition, with the name policy applied.
+pub(crate) struct TransitionLabel
+{
+    pub(crate) event: Option<String>,
+    pub(crate) guard: Option<String>,
+    pub(crate) actions: Vec<String>
+}
+
+impl TransitionLabel
+{
+    pub(crate) fn new(row: &Row, options: &DiagramOptions) -> Self
+    {
+        let namePolicy = &options.namePolicy;
+        Self{
+            event: shouldBeShown(&row.event).then(|| namePolicy.apply(&row.event)),
+            guard: row.guard.as_ref()
+                .map(|guard| GuardExpression::new(guard).render(&|functor| namePolicy.apply(&functor.to_string()))),
+            actions: match &row.action {
+                Some(action) => flattenActions(action).iter().map(|action| namePolicy.apply(&action.to_string())).collect(),
+                None => vec![]
+            }
+        }
+    }
+
+    /// Makes the lines shown in diagrams, hiding parts and following the label template as requested in the options.
+    pub(crate) fn makeDiagramLines(&self, options: &DiagramOptions) -> Vec<String>
+    {
+        let label = Self{
+            event: self.event.clone(),
+            guard: self.guard.clone().filter(|_| !options.hidesGuards),
+            actions: match options.hidesActions {
+                true => vec![],
+                false => self.actions.clone()
+            }
+        };
+        match &options.labelTemplate {
+            Some(labelTemplate) => labelTemplate.render(&label),
+            None => label.makeLines(options.actionListLayout)
+        }
+    }
+
+    pub(crate) fn makeLines(&self, actionListLayout: ActionListLayout) -> Vec<String>
+    {
+        let mut lines = vec![];
+        if let Some(event) = &self.event {
+            lines.push(format!("on {}", event));
+        }
+        if let Some(guard) = &self.guard {
+            lines.push(format!("if {}", guard));
+        }
+        match (actionListLayout, self.actions.is_empty()) {
+            (_, true) => (),
+            (ActionListLayout::Inline, false) => lines.push(format!("do {}", self.actions.join("; "))),
+            (ActionListLayout::Lines, false) => lines.extend(self.actions.iter().map(|action| format!("do {}", action)))
+        }
+        lines
+    }
+}
+
+/// Tells whether a name is not empty and is not `boost::msm::front::none`, written with any namespace qualification.
+pub(crate) fn shouldBeShown(name: &str) -> bool
+{
+    let unqualifiedName = match name.rfind("::") {
+        Some(index) => &name[index + 2..],
+        None => name
+    };
+    !matches!(unqualifiedName, "" | "None" | "none")
+}
@@ -0,0 +1,109 @@
+use regex::Regex;
+
+
+const DEFAULT_MACHINE_NAME: &str = "StateMachine";
+
+pub(crate) struct TransitionTableLocation
+{
+    pub(crate) machineName: String,
+    /// The name of the machine definition with its enclosing namespaces and structs.
+    pub(crate) qualifiedMachineName: String,
+    /// The namespaces enclosing the machine definition, joined with `::`, in which names of its rows are looked up.
+    pub(crate) machineNamespace: Option<String>,
+    pub(crate) start: usize,
+    /// The type declared as `initial_state` in the machine definition, as written.
+    pub(crate) initialState: Option<String>
+}
+
+pub(crate) fn findTransitionTables(text: &str) -> Vec<TransitionTableLocation>
+{
+    let transitionTableRegex = Regex::new(r"(?m)^[ \t]*struct transition_table\b").unwrap();
+    transitionTableRegex.find_iter(text)
+        .map(|found| {
+            let enclosingStruct = findEnclosingStruct(&text[..found.start()]);
+            TransitionTableLocation{
+                machineName: enclosingStruct.as_ref().map_or_else(|| DEFAULT_MACHINE_NAME.into(), |found| found.name.clone()),
+                qualifiedMachineName: enclosingStruct.as_ref()
+                    .map_or_else(|| DEFAULT_MACHINE_NAME.into(), |found| found.qualifiedName.clone()),
+                machineNamespace: enclosingStruct.as_ref().and_then(|found| found.namespace.clone()),
+                start: found.start(),
+                initialState: enclosingStruct.and_then(|found| findInitialState(&text[found.bodyStart..]))
+            }
+        })
+        .collect()
+}
+
+struct EnclosingStruct
+{
+    name: String,
+    qualifiedName: String,
+    namespace: Option<String>,
+    /// The index after the opening brace of the struct.
+    bodyStart: usize
+}
+
+/// A scope opened by a brace, named when it is a namespace, a struct or a class.
+struct Scope
+{
+    name: Option<String>,
+    isStruct: bool,
+    end: usize
+}
+
+/// Returns the innermost named struct or class enclosing the end of the text.
+fn findEnclosingStruct(textBefore: &str) -> Option<EnclosingStruct>
+{
+    let scopeRegex = Regex::new(r"\b(?:struct|class)\s+(\w+)[^;{}()]*\{|\bnamespace\s+([\w:]+)\s*\{|\{|\}").unwrap();
+    let mut scopes: Vec<Scope> = vec![];
+    for captures in scopeRegex.captures_iter(textBefore) {
+        let scope = captures.get(0).unwrap();
+        match scope.as_str() {
+            "}" => { scopes.pop(); },
+            _ => scopes.push(Scope{
+                name: captures.get(1).or(captures.get(2)).map(|name| name.as_str().to_string()),
+                isStruct: captures.get(1).is_some(),
+                end: scope.end()
+            })
+        }
+    }
+    let structIndex = scopes.iter().rposition(|scope| scope.isStruct)?;
+    let names: Vec<&str> = scopes[..=structIndex].iter().filter_map(|scope| scope.name.as_deref()).collect();
+    let qualifiedName = names.join("::");
+    let namespaces: Vec<&str> = scopes[..structIndex].iter()
+        .filter(|scope| !scope.isStruct)
+        .filter_map(|scope| scope.name.as_deref())
+        .collect();
+    let namespace = match namespaces.is_empty() {
+        true => None,
+        false => Some(namespaces.join("::"))
+    };
+    let structScope = &scopes[structIndex];
+    Some(EnclosingStruct{name: structScope.name.clone()?, qualifiedName, namespace, bodyStart: structScope.end})
+}
+
+/// Finds `using initial_state = X;` or `typedef X initial_state;` directly in the body starting the text.
+fn findInitialState(body: &str) -> Option<String>
+{
+    let initialStateRegex =
+        Regex::new(r"\busing\s+initial_state\s*=\s*([^;{}]+);|\btypedef\s+([^;{}]+?)\s*\binitial_state\s*;").unwrap();
+    let mut depth = 1;
+    let mut position = 0;
+    for captures in initialStateRegex.captures_iter(body) {
+        let found = captures.get(0).unwrap();
+        for character in body[position..found.start()].chars() {
+            match character {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => ()
+            }
+            if depth == 0 {
+                return None;
+            }
+        }
+        position = found.start();
+        if depth == 1 {
+            return captures.get(1).or(captures.get(2)).map(|state| state.as_str().trim().to_string());
+        }
+    }
+    None
+}
@@ -2,8 +2,31 @@ use crate::character_reader::CharacterReader;
 use crate::flow::Flow;
 use crate::token::Token;
 
+use std::ops::Range;
 
-pub(crate) fn lexTransitionTable(characterReader: CharacterReader) -> Vec<Token>
+
+/// Tokens together with the numbers of lines they start at, counted from 1 within the lexed text.
+pub(crate) struct LexedTokens
+{
+    pub(crate) tokens: Vec<Token>,
+    pub(crate) lines: Vec<usize>,
+    /// Byte ranges of the tokens within the lexed text.
+    pub(crate) spans: Vec<Range<usize>>,
+    /// Texts of `//` comments attached to each token: the ones on lines before it and the one following it on its line.
+    pub(crate) comments: Vec<Vec<String>>,
+    /// The byte offset after the semicolon ending the transition table, or the length of the text when it is missing.
+    pub(crate) end: usize
+}
+
+impl LexedTokens
+{
+    pub(crate) fn new() -> Self
+    {
+        Self{tokens: vec![], lines: vec![], spans: vec![], comments: vec![], end: 0}
+    }
+}
+
+pub(crate) fn lexTransitionTable(characterReader: CharacterReader) -> LexedTokens
 {
     let lexer = Lexer::new(characterReader);
     lexer.lex()
@@ -14,25 +37,76 @@ struct Lexer<'a>
     characterReader: CharacterReader<'a>,
     state: State,
     currentToken: String,
-    tokens: Vec<Token>
+    currentTokenLine: usize,
+    currentTokenStart: usize,
+    currentComment: String,
+    currentCommentLine: usize,
+    pendingComments: Vec<String>,
+    output: LexedTokens
 }
 
 impl<'a> Lexer<'a>
 {
     fn new(characterReader: CharacterReader<'a>) -> Self
     {
-        Self{characterReader, state: State::Empty, currentToken: String::new(), tokens: vec![]}
+        Self{
+            characterReader,
+            state: State::Empty,
+            currentToken: String::new(),
+            currentTokenLine: 0,
+            currentTokenStart: 0,
+            currentComment: String::new(),
+            currentCommentLine: 0,
+            pendingComments: vec![],
+            output: LexedTokens::new()
+        }
     }
 
-    fn lex(mut self) -> Vec<Token>
+    fn lex(mut self) -> LexedTokens
     {
         while let Some(character) = self.characterReader.next() {
-            match self.lexCharacter(character) {
-                Flow::Continue => (),
-                Flow::Break => break
+            if let Flow::Break = self.lexCharacter(character) {
+                break;
             }
         }
-        self.tokens
+        match self.state {
+            State::Collecting => self.pushCurrentToken(),
+            State::Comment => self.pushCurrentComment(),
+            State::Empty => ()
+        }
+        self.output.end = self.characterReader.offset();
+        self.output
+    }
+
+    fn pushToken(&mut self, text: &str, line: usize, start: usize)
+    {
+        self.output.tokens.push(Token::new(text));
+        self.output.lines.push(line);
+        self.output.spans.push(start..start + text.len());
+        self.output.comments.push(std::mem::take(&mut self.pendingComments));
+    }
+
+    /// Pushes a token of the symbol which was just read.
+    fn pushSymbol(&mut self, symbol: char)
+    {
+        let start = self.characterReader.offset() - symbol.len_utf8();
+        self.pushToken(&symbol.to_string(), self.characterReader.line(), start);
+    }
+
+    fn pushCurrentToken(&mut self)
+    {
+        let text = std::mem::take(&mut self.currentToken);
+        self.pushToken(&text, self.currentTokenLine, self.currentTokenStart);
+    }
+
+    /// Attaches the comment to the last token if it follows it on the same line, otherwise to the next one.
+    fn pushCurrentComment(&mut self)
+    {
+        let text = std::mem::take(&mut self.currentComment).trim().to_string();
+        match self.output.lines.last() {
+            Some(&line) if line == self.currentCommentLine => self.output.comments.last_mut().unwrap().push(text),
+            _ => self.pendingComments.push(text)
+        }
     }
 
     fn lexCharacter(&mut self, character: char) -> Flow
@@ -59,29 +133,32 @@ impl<'a> Lexer<'a>
     {
         match self.characterReader.peek() {
             Some('/') => {
+                self.currentCommentLine = self.characterReader.line();
                 self.characterReader.next();
                 self.state = State::Comment;
             },
-            _ => self.tokens.push(Token::new("/"))
+            _ => self.pushSymbol('/')
         }
         Flow::Continue
     }
 
     fn lexSymbolInEmptyState(&mut self, symbol: char) -> Flow
     {
-        self.tokens.push(Token::new(&symbol.to_string()));
+        self.pushSymbol(symbol);
         Flow::Continue
     }
 
     fn lexSemicolonInEmptyState(&mut self) -> Flow
     {
-        self.tokens.push(Token::new(";"));
+        self.pushSymbol(';');
         Flow::Break
     }
 
     fn lexIdentifierInEmptyState(&mut self, character: char) -> Flow
     {
         self.currentToken.push(character);
+        self.currentTokenLine = self.characterReader.line();
+        self.currentTokenStart = self.characterReader.offset() - character.len_utf8();
         self.state = State::Collecting;
         Flow::Continue
     }
@@ -90,14 +167,12 @@ impl<'a> Lexer<'a>
     {
         match character {
             x if x.is_whitespace() => {
-                self.tokens.push(Token::new(&self.currentToken));
-                self.currentToken.clear();
+                self.pushCurrentToken();
                 self.state = State::Empty;
             },
             '<' | '>' | ',' => {
-                self.tokens.push(Token::new(&self.currentToken));
-                self.tokens.push(Token::new(&character.to_string()));
-                self.currentToken.clear();
+                self.pushCurrentToken();
+                self.pushSymbol(character);
                 self.state = State::Empty;
             },
             _ => {
@@ -109,8 +184,12 @@ impl<'a> Lexer<'a>
 
     fn lexCharacterInCommentState(&mut self, character: char) -> Flow
     {
-        if character == '\n' {
-            self.state = State::Empty
+        match character {
+            '\n' => {
+                self.pushCurrentComment();
+                self.state = State::Empty;
+            },
+            _ => self.currentComment.push(character)
         }
         Flow::Continue
     }
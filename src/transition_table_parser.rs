@@ -1,41 +1,71 @@
 use crate::row::{Row, RowKind};
-use crate::row_section_parser::RowSectionParser;
 use crate::token::Token;
+use crate::transition_table_lexer::LexedTokens;
+use crate::type_expression_parser::parseRowSection;
 
 use regex::Regex;
 use std::iter::Peekable;
+use std::ops::Range;
 use std::slice::Iter;
 
 
-pub(crate) fn parseTransitionTable(tokens: &[Token]) -> Result<Vec<Row>,String>
+pub(crate) const ROW_IDENTIFIER_PATTERN: &str = ".*[rR]ow$";
+
+/// A message of a syntax error together with the line and the byte range of the token it was found at.
+#[derive(Debug)]
+pub(crate) struct ParseError
+{
+    pub(crate) line: usize,
+    pub(crate) span: Range<usize>,
+    pub(crate) message: String
+}
+
+pub(crate) fn parseTransitionTable(lexedTokens: &LexedTokens) -> Result<Vec<Row>,ParseError>
 {
     let parser = Parser::new();
-    parser.parse(tokens)
+    parser.parse(lexedTokens)
 }
 
 struct Parser
 {
     state: State,
     rowRegex: Regex,
-    rows: Vec<Row>
+    rows: Vec<Row>,
+    rowTokenIndexes: Vec<usize>,
+    currentLine: usize,
+    currentTokenIndex: usize
 }
 
 impl Parser
 {
     fn new() -> Self
     {
-        Self{state: State::ExpectRowIdentifier, rowRegex: Regex::new(".*[rR]ow$").unwrap(), rows: vec![]}
+        Self{
+            state: State::ExpectRowIdentifier,
+            rowRegex: Regex::new(ROW_IDENTIFIER_PATTERN).unwrap(),
+            rows: vec![],
+            rowTokenIndexes: vec![],
+            currentLine: 0,
+            currentTokenIndex: 0
+        }
     }
 
-    fn parse(mut self, tokens: &[Token]) -> Result<Vec<Row>,String>
+    fn parse(mut self, lexedTokens: &LexedTokens) -> Result<Vec<Row>,ParseError>
     {
+        let tokens = &lexedTokens.tokens;
         let firstRowIndex = match self.findFirstRow(tokens) {
             Some(index) => index,
-            None => return Err("Rows were not found in the transition table.".into())
+            None => {
+                let line = lexedTokens.lines.first().copied().unwrap_or_default();
+                let span = lexedTokens.spans.first().cloned().unwrap_or_default();
+                return Err(ParseError{line, span, message: "Rows were not found in the transition table.".into()});
+            }
         };
 
         let mut iterator = tokens[firstRowIndex..].iter().peekable();
-        while let Some(token) = iterator.peek() {
+        while let Some(&token) = iterator.peek() {
+            self.currentTokenIndex = tokens.len() - iterator.len();
+            self.currentLine = lexedTokens.lines[self.currentTokenIndex];
             match self.parseToken(token, &mut iterator) {
                 Ok(flow) =>
                     match flow {
@@ -43,20 +73,32 @@ impl Parser
                         Flow::ContinueWithoutConsuming => (),
                         Flow::Break => break
                     },
-                Err(e) => return Err(e)
+                Err(message) => {
+                    let span = lexedTokens.spans[self.currentTokenIndex].clone();
+                    return Err(ParseError{line: self.currentLine, span, message});
+                }
             }
         }
 
+        self.attachComments(&lexedTokens.comments);
         Ok(self.rows)
     }
 
+    /// Gives each row the comments of the tokens from its identifier up to the identifier of the next row.
+    fn attachComments(&mut self, comments: &[Vec<String>])
+    {
+        let rowEnds = self.rowTokenIndexes.iter().skip(1).copied().chain([comments.len()]);
+        for ((row, &rowStart), rowEnd) in self.rows.iter_mut().zip(&self.rowTokenIndexes).zip(rowEnds) {
+            row.comments = comments[rowStart..rowEnd].concat();
+        }
+    }
+
     fn findFirstRow(&self, tokens: &[Token]) -> Option<usize>
     {
         for (index, token) in tokens.iter().enumerate() {
-            if let Token::Identifier(name) = token {
-                if self.rowRegex.is_match(name) {
-                    return Some(index);
-                }
+            if let Token::Identifier(name) = token
+                && self.rowRegex.is_match(name) {
+                return Some(index);
             }
         }
         None
@@ -86,7 +128,8 @@ impl Parser
         match token {
             Token::Identifier(name) => {
                 if self.rowRegex.is_match(name) {
-                    self.rows.push(Row::new(selectRowKind(name)));
+                    self.rows.push(Row::new(selectRowKind(name), self.currentLine));
+                    self.rowTokenIndexes.push(self.currentTokenIndex);
                     self.state = State::ExpectRowTemplateStart;
                     Ok(Flow::Continue)
                 } else {
@@ -186,10 +229,9 @@ impl Parser
 
     fn parseTokenInExpectAction(&mut self, iterator: &mut Peekable<Iter<Token>>) -> Result<Flow,String>
     {
-        let rowSectionParser = RowSectionParser::new("an action");
-        match rowSectionParser.parse(iterator) {
-            Ok(name) => {
-                self.getLastRow().action = name;
+        match parseRowSection("an action", iterator) {
+            Ok(action) => {
+                self.getLastRow().action = Some(action);
                 self.state = State::AfterAction;
                 Ok(Flow::ContinueWithoutConsuming)
             },
@@ -214,10 +256,9 @@ impl Parser
 
     fn parseTokenInExpectGuard(&mut self, iterator: &mut Peekable<Iter<Token>>) -> Result<Flow,String>
     {
-        let rowSectionParser = RowSectionParser::new("a guard");
-        match rowSectionParser.parse(iterator) {
-            Ok(name) => {
-                self.getLastRow().guard = name;
+        match parseRowSection("a guard", iterator) {
+            Ok(guard) => {
+                self.getLastRow().guard = Some(guard);
                 self.state = State::ExpectRowEnd;
                 Ok(Flow::ContinueWithoutConsuming)
             },
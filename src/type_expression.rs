@@ -0,0 +1,49 @@
+use std::fmt::{Display, Formatter};
+
+
+/// A C++ type used as a section of a row, like `Guard`, `And_<Guard1, Not_<Guard2>>` or `&Machine::action`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TypeExpression
+{
+    Name(String),
+    Number(String),
+    Template{name: String, arguments: Vec<TypeExpression>},
+    Member{scope: Box<TypeExpression>, name: String},
+    Address(Box<TypeExpression>)
+}
+
+impl TypeExpression
+{
+    /// Returns a copy of the expression with every name, including template names, transformed by `transform`.
+    /// Names of members accessed with `::` after a template are kept, as they depend on the template.
+    pub(crate) fn mapNames(&self, transform: &mut impl FnMut(&str) -> String) -> Self
+    {
+        match self {
+            Self::Name(name) => Self::Name(transform(name)),
+            Self::Number(_) => self.clone(),
+            Self::Template{name, arguments} => {
+                let name = transform(name);
+                let arguments = arguments.iter().map(|argument| argument.mapNames(transform)).collect();
+                Self::Template{name, arguments}
+            },
+            Self::Member{scope, name} => Self::Member{scope: Box::new(scope.mapNames(transform)), name: name.clone()},
+            Self::Address(expression) => Self::Address(Box::new(expression.mapNames(transform)))
+        }
+    }
+}
+
+impl Display for TypeExpression
+{
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            Self::Name(name) | Self::Number(name) => write!(formatter, "{}", name),
+            Self::Template{name, arguments} => {
+                let arguments: Vec<String> = arguments.iter().map(|argument| argument.to_string()).collect();
+                write!(formatter, "{}<{}>", name, arguments.join(", "))
+            },
+            Self::Member{scope, name} => write!(formatter, "{}::{}", scope, name),
+            Self::Address(expression) => write!(formatter, "&{}", expression)
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::character_reader::CharacterReader;
+use crate::token::Token;
+use crate::transition_table_lexer::lexTransitionTable;
+use crate::type_expression::TypeExpression;
+
+use std::iter::Peekable;
+use std::slice::Iter;
+
+
+const MAX_NESTING_DEPTH: usize = 128;
+
+/// Parses a section of a row, like an action or a guard, leaving the token following it unconsumed.
+pub(crate) fn parseRowSection(name: &'static str, iterator: &mut Peekable<Iter<Token>>) -> Result<TypeExpression,String>
+{
+    let mut parser = TypeExpressionParser{name, iterator, depth: 0};
+    parser.parseExpression(&|token| format!("Expected {}, got: {:?}.", name, token))
+}
+
+/// Parses a standalone type, e.g. the target of an alias declaration.
+pub(crate) fn parseTypeExpression(text: &str) -> Result<TypeExpression,String>
+{
+    let mut tokens = lexTransitionTable(CharacterReader::new(text)).tokens;
+    tokens.push(Token::InstructionEnd);
+    let mut iterator = tokens.iter().peekable();
+    let expression = parseRowSection("a type", &mut iterator)?;
+    match iterator.next() {
+        Some(Token::InstructionEnd) => Ok(expression),
+        token => Err(format!("Expected an end of a type, got: {:?}.", token))
+    }
+}
+
+struct TypeExpressionParser<'a, 'b>
+{
+    name: &'static str,
+    iterator: &'a mut Peekable<Iter<'b, Token>>,
+    depth: usize
+}
+
+impl TypeExpressionParser<'_, '_>
+{
+    fn parseExpression(&mut self, makeUnexpectedTokenError: &dyn Fn(&Token) -> String) -> Result<TypeExpression,String>
+    {
+        let mut isAddress = false;
+        if let Token::Identifier(text) = self.peek()?
+            && text == "&" {
+            self.iterator.next();
+            isAddress = true;
+        }
+
+        let mut name = match self.peek()? {
+            Token::Identifier(text) => text.clone(),
+            token => return Err(makeUnexpectedTokenError(token))
+        };
+        self.iterator.next();
+        if let Some(stripped) = name.strip_prefix('&') {
+            name = stripped.into();
+            isAddress = true;
+        }
+        self.continueQualifiedName(&mut name)?;
+
+        let mut expression = match self.peek()? {
+            Token::TemplateStart => {
+                self.iterator.next();
+                TypeExpression::Template{name, arguments: self.parseTemplateArguments()?}
+            },
+            _ => makeNameOrNumber(name)
+        };
+        while let Token::Identifier(text) = self.peek()?
+            && text.starts_with("::") {
+            let mut memberName = text.clone();
+            self.iterator.next();
+            self.continueQualifiedName(&mut memberName)?;
+            let memberName = memberName.trim_start_matches("::").into();
+            expression = TypeExpression::Member{scope: Box::new(expression), name: memberName};
+        }
+
+        match isAddress {
+            true => Ok(TypeExpression::Address(Box::new(expression))),
+            false => Ok(expression)
+        }
+    }
+
+    /// Joins names split by whitespace around `::`, like `boost:: msm ::front`.
+    fn continueQualifiedName(&mut self, name: &mut String) -> Result<(),String>
+    {
+        loop {
+            let isContinued = name.ends_with("::");
+            match self.peek()? {
+                Token::Identifier(text) if isContinued || text.starts_with("::") => {
+                    name.push_str(text);
+                    self.iterator.next();
+                },
+                token if isContinued => return Err(format!("Expected a name after \"::\", got: {:?}.", token)),
+                _ => return Ok(())
+            }
+        }
+    }
+
+    fn parseTemplateArguments(&mut self) -> Result<Vec<TypeExpression>,String>
+    {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err(format!("While parsing {}, templates were nested too deeply.", self.name));
+        }
+
+        let mut arguments = vec![];
+        match self.peek()? {
+            Token::TemplateEnd => {
+                self.iterator.next();
+                self.depth -= 1;
+                return Ok(arguments);
+            },
+            Token::Identifier(_) => (),
+            token => return Err(format!("Expected an identifier or a template end, got: {:?}.", token))
+        }
+
+        loop {
+            let argument = self.parseExpression(&|token| format!("Expected an identifier, got: {:?}.", token))?;
+            let isPlainName = matches!(argument, TypeExpression::Name(_) | TypeExpression::Number(_));
+            arguments.push(argument);
+            match self.peek()? {
+                Token::Comma => { self.iterator.next(); },
+                Token::TemplateEnd => {
+                    self.iterator.next();
+                    self.depth -= 1;
+                    return Ok(arguments);
+                },
+                token if isPlainName => return Err(format!("Expected a comma, template start or template end, got: {:?}.", token)),
+                token => return Err(format!("Expected a comma or a template end, got: {:?}.", token))
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Result<&Token,String>
+    {
+        match self.iterator.peek() {
+            Some(token) => Ok(token),
+            None => Err(format!("While parsing {}, tokens ended prematurely.", self.name))
+        }
+    }
+}
+
+fn makeNameOrNumber(text: String) -> TypeExpression
+{
+    let digits = text.strip_prefix('-').unwrap_or(&text);
+    match digits.starts_with(|character: char| character.is_ascii_digit()) {
+        true => TypeExpression::Number(text),
+        false => TypeExpression::Name(text)
+    }
+}
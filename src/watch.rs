@@ -0,0 +1,124 @@
+use crate::analysis::analyzeInputFiles;
+use crate::cli::Options;
+use crate::input_files::FileFilter;
+
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::{Duration, Instant};
+
+
+/// Time files have to stay unchanged before they are read, so that a burst of saves causes a single regeneration.
+const DEBOUNCE_TIME: Duration = Duration::from_millis(300);
+
+/// Regenerates diagrams whenever the watched files change, until the program is interrupted. Errors and findings of the
+/// analyze command are printed instead of ending the program.
+pub(crate) fn watchInputFiles(options: &Options, generate: impl Fn(&Options) -> Result<(),String>) -> Result<(),String>
+{
+    let mut watcher = FileWatcher::new(options)?;
+    loop {
+        match generate(options).and_then(|_| analyzeInputFiles(options)) {
+            Ok(report) => eprint!("{}", report.text),
+            Err(e) => eprintln!("Error: {:?}", e)
+        }
+        eprintln!("Watching for changes, press Ctrl+C to stop.");
+        watcher.waitForChange()?;
+    }
+}
+
+/// Receives notifications about changes of input files, including ones added to or removed from input directories, and
+/// of the labels, header and footer files.
+pub(crate) struct FileWatcher
+{
+    /// Kept only to keep receiving events, which stop when it is dropped.
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    files: Vec<PathBuf>,
+    directories: Vec<PathBuf>,
+    filter: FileFilter
+}
+
+impl FileWatcher
+{
+    /// Starts watching. Files are watched through their directories, because editors often save a file by replacing it,
+    /// which ends watching the replaced file itself.
+    pub(crate) fn new(options: &Options) -> Result<Self,String>
+    {
+        let (sender, events) = channel();
+        let mut watcher = match notify::recommended_watcher(sender) {
+            Ok(watcher) => watcher,
+            Err(e) => return Err(format!("Failed to watch files, error: {}", e))
+        };
+        let mut files = vec![];
+        let mut directories = vec![];
+        let styleFiles = options.labelsPath.iter().chain(&options.headerPath).chain(&options.footerPath);
+        for path in options.inputPaths.iter().chain(styleFiles) {
+            let path = makeAbsolute(path)?;
+            let (watchedPath, mode) = match path.is_dir() {
+                true => (path.clone(), RecursiveMode::Recursive),
+                false => (path.parent().unwrap_or(&path).to_path_buf(), RecursiveMode::NonRecursive)
+            };
+            if let Err(e) = watcher.watch(&watchedPath, mode) {
+                return Err(format!("Failed to watch: {:?}, error: {}", watchedPath, e));
+            }
+            match mode {
+                RecursiveMode::Recursive => directories.push(path),
+                RecursiveMode::NonRecursive => files.push(path)
+            }
+        }
+        let filter = FileFilter::new(&options.includePatterns, &options.excludePatterns)?;
+        Ok(Self{_watcher: watcher, events, files, directories, filter})
+    }
+
+    /// Blocks until a watched file changes and then no more changes of watched files come for the debounce time.
+    pub(crate) fn waitForChange(&mut self) -> Result<(),String>
+    {
+        loop {
+            match self.events.recv() {
+                Ok(event) if self.isRelevant(&event) => break,
+                Ok(_) => (),
+                Err(_) => return Err("Watching files stopped unexpectedly.".into())
+            }
+        }
+        let mut deadline = Instant::now() + DEBOUNCE_TIME;
+        loop {
+            match self.events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(event) if self.isRelevant(&event) => deadline = Instant::now() + DEBOUNCE_TIME,
+                Ok(_) => (),
+                Err(RecvTimeoutError::Timeout) => return Ok(()),
+                Err(RecvTimeoutError::Disconnected) => return Err("Watching files stopped unexpectedly.".into())
+            }
+        }
+    }
+
+    /// Tells whether the event changed a watched file, skipping reads, like the ones of regenerating diagrams, and files
+    /// in input directories not matching the include and exclude patterns, like generated diagrams.
+    fn isRelevant(&self, event: &notify::Result<Event>) -> bool
+    {
+        let event = match event {
+            Ok(event) => event,
+            Err(_) => return true
+        };
+        match event.kind {
+            EventKind::Access(AccessKind::Close(AccessMode::Write)) => (),
+            EventKind::Access(_) => return false,
+            _ => ()
+        }
+        event.paths.iter().any(|path| {
+            self.files.contains(path) || self.directories.iter().any(|directory| match path.strip_prefix(directory) {
+                Ok(relativePath) => self.filter.isMatch(relativePath),
+                Err(_) => false
+            })
+        })
+    }
+}
+
+/// Makes the path absolute without resolving symbolic links, like the paths in events are.
+fn makeAbsolute(path: &Path) -> Result<PathBuf,String>
+{
+    match std::path::absolute(path) {
+        Ok(path) => Ok(path),
+        Err(e) => Err(format!("Failed to watch: {:?}, error: {}", path, e))
+    }
+}
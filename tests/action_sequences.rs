@@ -0,0 +1,80 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+fn makeCppFileContent(action: &str) -> String
+{
+    format!(r#"
+#include <boost/msm/back/state_machine.hpp>
+#include <boost/msm/front/state_machine_def.hpp>
+#include <boost/msm/front/functor_row.hpp>
+
+namespace msmf = boost::msm::front;
+namespace mpl = boost::mpl;
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{{
+    using initial_state = State1;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<State1, Event, State2, {}, msmf::none>
+    > {{}};
+}};
+"#, action)
+}
+
+fn makeExpectedOutput(actionText: &str) -> String
+{
+    format!("@startuml\nhide empty description\n[*] --> State1\nState1 --> State2 : on Event\\n{}\n@enduml\n", actionText)
+}
+
+#[test]
+fn shouldRenderActionsInOrder_whenActionIsActionSequence()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("msmf::ActionSequence_<mpl::vector<A, B, C>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do A; B; C"));
+}
+
+#[test]
+fn shouldFlattenNestedActionSequences()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent(
+            "msmf::ActionSequence_<mpl::vector<A, boost::msm::front::ActionSequence_<mpl::vector<B, C>>, D>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do A; B; C; D"));
+}
+
+#[test]
+fn shouldRenderOneActionPerLine_whenLinesLayoutIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--action-list", "lines", "-"])
+        .write_stdin(makeCppFileContent("msmf::ActionSequence_<mpl::vector<A, Log<B>>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput(r"do A\ndo Log<B>"));
+}
+
+#[test]
+fn shouldRenderActionList_whenMermaidFormatIsSelected()
+{
+    let expectedOutput =
+"stateDiagram-v2
+    [*] --> State1
+    State1 --> State2 : on Event<br>do A#59; B
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "mermaid", "-"])
+        .write_stdin(makeCppFileContent("msmf::ActionSequence_<mpl::vector<A, B>>"))
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFail_whenUnknownActionListLayoutIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--action-list", "table", "-"]).assert().failure()
+        .stderr("Error: \"Unknown action list layout: table, expected one of: inline, lines.\"\n");
+}
@@ -0,0 +1,221 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+fn makeCppFileContent(initialState: &str, rows: &str) -> String
+{
+    format!(r#"struct Stopped : public boost::msm::front::terminate_state<> {{}};
+
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{{
+    {}
+
+    struct transition_table : boost::mpl::vector<
+{}
+    > {{}};
+}};
+"#, initialState, rows)
+}
+
+#[test]
+fn shouldReportNothing_whenEveryStateIsReachableAndHasOutgoingTransitions()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldReportNothing_whenRowIsInternalTransition()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        Row<Busy, Tick, none, Count, none>,\n        _row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldReportUnreachableState_withLinesOfRowsMentioningIt()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, Stop, Idle>,\n        _row<Orphan, Start, Busy>,\n        _row<Orphan, Reset, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:10: warning: state Orphan in MachineDef is unreachable from the initial state\n",
+            "<stdin>:11: note: Orphan is also mentioned here\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldReportDeadEndState_whenItIsNotTerminal()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Idle, Stop, Stopped>,\n        _row<Busy, Fail, Broken>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:10: warning: state Broken in MachineDef has no outgoing transitions\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldStartFromEveryRegion_whenInitialStateIsSequence()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, Stop, Idle>,\n        _row<Watching, Tick, Watching>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("typedef boost::mpl::vector<Idle, Watching> initial_state;", rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldStartFromFirstRow_whenInitialStateIsMissing()
+{
+    let rows = "        _row<Busy, Stop, Idle>,\n        _row<Idle, Start, Busy>,\n        _row<Orphan, Start, Busy>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("", rows))
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:10: warning: state Orphan in MachineDef is unreachable from the initial state\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldReportLineOfAlias_whenRowIsDeclaredWithTypedef()
+{
+    let source = r#"struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+    typedef _row<Busy, Fail, Broken> FailRow;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        FailRow
+    > {};
+};
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(source)
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:8: warning: state Broken in MachineDef has no outgoing transitions\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldFail_whenOutputDirectoryIsUsedWithAnalyze()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "--output-dir", "out", "file.cpp"])
+        .assert().failure()
+        .stderr("Error: \"Option --output-dir cannot be used with the analyze command.\"\n");
+}
+
+#[test]
+fn shouldReportConflictingTransitions_whenRowsHaveNoGuards()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, Stop, Idle>,\n        _row<Idle, Start, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:10: warning: conflicting transitions from Idle on Start in MachineDef, boost::msm tries this row first\n",
+            "<stdin>:8: note: this row is never taken\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldReportConflictingTransitions_whenGuardsAreIdentical()
+{
+    let rows = "        g_row<Idle, Start, Busy, &MachineDef::isReady>,\n        g_row<Idle, Start, Idle, &MachineDef::isReady>,\n        _row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:9: warning: conflicting transitions from Idle on Start in MachineDef, boost::msm tries this row first\n",
+            "<stdin>:8: note: this row is never taken\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldWarnAboutOverlappingTransitions_whenGuardsDiffer()
+{
+    let rows = "        row<Idle, Start, Busy, none, IsReady>,\n        _row<Busy, Stop, Idle>,\n        row<Idle, Start, Idle, none, IsBlocked>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout(concat!(
+            "<stdin>:10: warning: guarded transitions from Idle on Start in MachineDef may overlap, boost::msm tries this row first\n",
+            "<stdin>:8: note: this row is taken only when the guard of the other one does not hold\n",
+            "Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"));
+}
+
+#[test]
+fn shouldNotReportTransitions_whenGuardsExcludeEachOther()
+{
+    let rows = "        row<Idle, Start, Busy, none, IsReady>,\n        row<Idle, Start, Idle, none, Not_<IsReady>>,\n        _row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldNotReportTransitions_whenOnlyLaterRowHasGuard()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        g_row<Idle, Start, Idle, &MachineDef::isBlocked>,\n        _row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldReportError_whenAnonymousTransitionsWithoutGuardsFormCycle()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, none, Checking>,\n        _row<Checking, none, Busy>,\n        \
+_row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().failure()
+        .stdout(concat!(
+            "<stdin>:9: error: anonymous transitions without guards between Busy, Checking in MachineDef form a cycle, ",
+            "boost::msm takes them forever\n",
+            "<stdin>:10: note: this row is also a part of the cycle\n",
+            "Summary: 1 error(s) and 0 warning(s) in 1 machine(s).\n"))
+        .stderr("Error: \"Found 1 error(s).\"\n");
+}
+
+#[test]
+fn shouldReportEveryCycle_whenAnonymousTransitionsFormSeveralCycles()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, none, Busy>,\n        _row<Idle, Stop, Checking>,\n        \
+_row<Checking, none, Waiting>,\n        _row<Waiting, none, Checking>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().failure()
+        .stdout(concat!(
+            "<stdin>:9: error: anonymous transitions without guards between Busy in MachineDef form a cycle, ",
+            "boost::msm takes them forever\n",
+            "<stdin>:11: error: anonymous transitions without guards between Checking, Waiting in MachineDef form a cycle, ",
+            "boost::msm takes them forever\n",
+            "<stdin>:12: note: this row is also a part of the cycle\n",
+            "Summary: 2 error(s) and 0 warning(s) in 1 machine(s).\n"))
+        .stderr("Error: \"Found 2 error(s).\"\n");
+}
+
+#[test]
+fn shouldNotReportAnonymousTransitionCycle_whenItHasGuard()
+{
+    let rows = "        _row<Idle, Start, Busy>,\n        _row<Busy, none, Checking>,\n        \
+g_row<Checking, none, Busy, &MachineDef::isBusy>,\n        _row<Busy, Stop, Idle>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["analyze", "-"])
+        .write_stdin(makeCppFileContent("using initial_state = Idle;", rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
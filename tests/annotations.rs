@@ -0,0 +1,86 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+// @msm-label "Wait for user"
+// Shown after start up.
+struct Idle : public msmf::state<> {};
+// @msm-label "Busy" is ignored here, as it is not above a struct.
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        // @msm-note Sent by the start button
+        msmf::Row<Idle, Start, Busy, msmf::none, msmf::none>,
+        msmf::Row<Busy, Stop, Idle, msmf::none, msmf::none> // @msm-note "Also sent on timeout"
+    > {};
+};
+"#;
+
+#[test]
+fn shouldShowLabelsAndNotesFromAnnotations_whenPlantUmlFormatIsSelected()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "Wait for user" as Idle
+[*] --> Idle
+Idle --> Busy : on Start
+note on link
+Sent by the start button
+end note
+Busy --> Idle : on Stop
+note on link
+Also sent on timeout
+end note
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldAddNotesToLabels_whenMermaidFormatIsSelected()
+{
+    let expectedOutput =
+"stateDiagram-v2
+    state \"Wait for user\" as Idle
+    [*] --> Idle
+    Idle --> Busy : on Start<br>Sent by the start button
+    Busy --> Idle : on Stop<br>Also sent on timeout
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "mermaid", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldPreferLabelsFile_overAnnotations()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let labelsPath = directory.path().join("labels.toml");
+    std::fs::write(&labelsPath, "[labels]\nIdle = \"Idle screen\"\n").unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--labels").arg(&labelsPath).args(["--format", "dot", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains("    \"Idle\" [label=\"Idle screen\"];\n"))
+        .stdout(predicates::str::contains("    \"Idle\" -> \"Busy\" [label=\"on Start\\nSent by the start button\"];\n"));
+}
+
+#[test]
+fn shouldEscapeQuotesAndLineBreaksInLabels_whenPlantUmlFormatIsSelected()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let labelsPath = directory.path().join("labels.toml");
+    std::fs::write(&labelsPath, "[labels]\nIdle = \"Say \\\"hi\\\"\\nand wait\"\n").unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--labels").arg(&labelsPath).arg("-")
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains("state \"Say <U+0022>hi<U+0022>\\nand wait\" as Idle\n"));
+}
@@ -14,10 +14,10 @@ fn shouldFail_whenNoFilePathIsProvided()
 }
 
 #[test]
-fn shouldFail_whenTooManyArgumentsAreProvided()
+fn shouldFail_whenMultipleFilesAreProvidedWithoutOutputDirectory()
 {
-    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(&["file1", "file2"]).assert().failure()
-        .stderr("Error: \"Too many arguments passed to program, expected only one with a file path, got 2\"\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["file1", "file2"]).assert().failure()
+        .stderr("Error: \"Please provide an output directory with --output-dir when analyzing multiple files or directories.\"\n");
 }
 
 #[test]
@@ -0,0 +1,148 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::path::Path;
+
+
+fn makeMachineSource(machineName: &str, startState: &str, targetState: &str) -> String
+{
+    format!(r#"
+#include <boost/msm/back/state_machine.hpp>
+#include <boost/msm/front/state_machine_def.hpp>
+
+struct {start} : public boost::msm::front::state<> {{}};
+struct {target} : public boost::msm::front::state<> {{}};
+struct Event {{}};
+
+struct {machine} : public boost::msm::front::state_machine_def<{machine}>
+{{
+    using initial_state = {start};
+
+    struct transition_table : boost::mpl::vector<
+        _row<{start}, Event, {target}>
+    > {{}};
+}};
+"#, machine = machineName, start = startState, target = targetState)
+}
+
+fn makeExpectedDiagram(startState: &str, targetState: &str) -> String
+{
+    format!("@startuml\nhide empty description\n[*] --> {start}\n{start} --> {target} : on Event\n@enduml\n",
+        start = startState, target = targetState)
+}
+
+fn readFile(path: &Path) -> String
+{
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn shouldGenerateDiagramPerMachine_whenDirectoryIsProvided()
+{
+    let inputDirectory = tempfile::tempdir().unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    std::fs::create_dir(inputDirectory.path().join("nested")).unwrap();
+    std::fs::write(inputDirectory.path().join("first.cpp"), makeMachineSource("FirstMachineDef", "Idle", "Busy")).unwrap();
+    std::fs::write(inputDirectory.path().join("nested/second.hpp"), makeMachineSource("SecondMachineDef", "Open", "Closed")).unwrap();
+    std::fs::write(inputDirectory.path().join("nested/other.cpp"), "int main() { return 0; }").unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg(inputDirectory.path()).arg("--output-dir").arg(outputDirectory.path())
+        .assert().success()
+        .stdout(predicates::str::contains(
+            "Summary: 2 diagram(s) generated, 0 file(s) failed, 1 file(s) without a transition table skipped.\n"));
+
+    assert_eq!(readFile(&outputDirectory.path().join("FirstMachineDef.puml")), makeExpectedDiagram("Idle", "Busy"));
+    assert_eq!(readFile(&outputDirectory.path().join("SecondMachineDef.puml")), makeExpectedDiagram("Open", "Closed"));
+}
+
+#[cfg(unix)]
+#[test]
+fn shouldSkipLinkedDirectories_whenDirectoryLinksToItsParent()
+{
+    let inputDirectory = tempfile::tempdir().unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    std::fs::write(inputDirectory.path().join("first.cpp"), makeMachineSource("FirstMachineDef", "Idle", "Busy")).unwrap();
+    std::os::unix::fs::symlink(inputDirectory.path(), inputDirectory.path().join("loop")).unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg(inputDirectory.path()).arg("--output-dir").arg(outputDirectory.path())
+        .assert().success()
+        .stdout(predicates::str::contains(
+            "Summary: 1 diagram(s) generated, 0 file(s) failed, 0 file(s) without a transition table skipped.\n"));
+}
+
+#[test]
+fn shouldGenerateDiagramPerMachine_whenFileContainsMultipleMachines()
+{
+    let inputDirectory = tempfile::tempdir().unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    let filePath = inputDirectory.path().join("machines.cpp");
+    std::fs::write(&filePath,
+        makeMachineSource("FirstMachineDef", "Idle", "Busy") + &makeMachineSource("SecondMachineDef", "Open", "Closed")).unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg(&filePath).arg("--output-dir").arg(outputDirectory.path())
+        .assert().success();
+
+    assert_eq!(readFile(&outputDirectory.path().join("FirstMachineDef.puml")), makeExpectedDiagram("Idle", "Busy"));
+    assert_eq!(readFile(&outputDirectory.path().join("SecondMachineDef.puml")), makeExpectedDiagram("Open", "Closed"));
+}
+
+#[test]
+fn shouldGenerateDistinctFileNames_whenMachinesHaveTheSameName()
+{
+    let inputDirectory = tempfile::tempdir().unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    std::fs::write(inputDirectory.path().join("a.cpp"), makeMachineSource("MachineDef", "Idle", "Busy")).unwrap();
+    std::fs::write(inputDirectory.path().join("b.cpp"), makeMachineSource("MachineDef", "Open", "Closed")).unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg(inputDirectory.path()).arg("--output-dir").arg(outputDirectory.path())
+        .assert().success();
+
+    assert_eq!(readFile(&outputDirectory.path().join("MachineDef.puml")), makeExpectedDiagram("Idle", "Busy"));
+    assert_eq!(readFile(&outputDirectory.path().join("MachineDef-2.puml")), makeExpectedDiagram("Open", "Closed"));
+}
+
+#[test]
+fn shouldOnlyAnalyzeMatchingFiles_whenIncludeAndExcludePatternsAreProvided()
+{
+    let inputDirectory = tempfile::tempdir().unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    std::fs::create_dir(inputDirectory.path().join("generated")).unwrap();
+    std::fs::write(inputDirectory.path().join("machine.hpp"), makeMachineSource("HeaderMachineDef", "Idle", "Busy")).unwrap();
+    std::fs::write(inputDirectory.path().join("machine.cpp"), makeMachineSource("SourceMachineDef", "Idle", "Busy")).unwrap();
+    std::fs::write(inputDirectory.path().join("generated/machine.hpp"), makeMachineSource("GeneratedMachineDef", "Idle", "Busy")).unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg(inputDirectory.path()).arg("--output-dir").arg(outputDirectory.path())
+        .args(["--include", "*.hpp", "--exclude", "generated/**"])
+        .assert().success();
+
+    assert!(outputDirectory.path().join("HeaderMachineDef.puml").exists());
+    assert!(!outputDirectory.path().join("SourceMachineDef.puml").exists());
+    assert!(!outputDirectory.path().join("GeneratedMachineDef.puml").exists());
+}
+
+#[test]
+fn shouldReportFailedFilesAndContinue_whenSomeFilesCannotBeParsed()
+{
+    let inputDirectory = tempfile::tempdir().unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    let brokenFilePath = inputDirectory.path().join("broken.cpp");
+    std::fs::write(&brokenFilePath, "struct MachineDef {\n    struct transition_table : boost::mpl::vector<\n        _row<,>\n    > {};\n};\n").unwrap();
+    std::fs::write(inputDirectory.path().join("good.cpp"), makeMachineSource("MachineDef", "Idle", "Busy")).unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg(inputDirectory.path()).arg("--output-dir").arg(outputDirectory.path())
+        .assert().failure()
+        .stdout(predicates::str::contains(format!("Failed {}: Expected start state, got: Comma.\n", brokenFilePath.display())))
+        .stdout(predicates::str::contains(
+            "Summary: 1 diagram(s) generated, 1 file(s) failed, 0 file(s) without a transition table skipped.\n"))
+        .stderr("Error: \"Failed to process 1 file(s).\"\n");
+
+    assert!(outputDirectory.path().join("MachineDef.puml").exists());
+}
@@ -0,0 +1,145 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::io::Write;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+#include <boost/msm/back/state_machine.hpp>
+#include <boost/msm/front/state_machine_def.hpp>
+
+struct StartState : public boost::msm::front::state<> {};
+struct TargetState : public boost::msm::front::state<> {};
+struct Event {};
+
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    bool guard(const Event&)
+    {
+        return true;
+    }
+
+    using initial_state = StartState;
+    using M = MachineDef;
+
+    struct transition_table : boost::mpl::vector<
+        g_row<StartState, Event, TargetState, &M::guard>
+    > {};
+};
+"#;
+
+const PLANTUML_OUTPUT: &str =
+r"@startuml
+hide empty description
+[*] --> StartState
+StartState --> TargetState : on Event\nif &M::guard
+@enduml
+";
+
+#[test]
+fn shouldPrintHelp_whenHelpOptionIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--help").assert().success()
+        .stdout(predicates::str::starts_with("Generates state diagrams"))
+        .stdout(predicates::str::contains("Usage: boost-msm-grapher [COMMAND] [OPTIONS] <PATH>..."));
+}
+
+#[test]
+fn shouldPrintVersion_whenVersionOptionIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--version").assert().success()
+        .stdout(format!("{} {}\n", APP_NAME, env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn shouldFail_whenUnknownOptionIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--unknown").assert().failure()
+        .stderr("Error: \"Unknown option: --unknown, use --help to list available options.\"\n");
+}
+
+#[test]
+fn shouldFail_whenOptionValueIsMissing()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["file.cpp", "--output"]).assert().failure()
+        .stderr("Error: \"Option --output requires a value.\"\n");
+}
+
+#[test]
+fn shouldFail_whenUnknownFormatIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "svg", "file.cpp"]).assert().failure()
+        .stderr("Error: \"Unknown output format: svg, expected one of: plantuml, dot, mermaid, matrix.\"\n");
+}
+
+#[test]
+fn shouldReadFromStandardInput_whenPathIsDash()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(CPP_FILE_CONTENT).assert().success()
+        .stdout(PLANTUML_OUTPUT);
+}
+
+#[test]
+fn shouldWriteToOutputFile_whenOutputOptionIsProvided()
+{
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(CPP_FILE_CONTENT.as_bytes()).unwrap();
+    let outputDirectory = tempfile::tempdir().unwrap();
+    let outputPath = outputDirectory.path().join("diagram.puml");
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).arg("-o").arg(&outputPath).assert().success()
+        .stdout("");
+    assert_eq!(std::fs::read_to_string(&outputPath).unwrap(), PLANTUML_OUTPUT);
+}
+
+#[test]
+fn shouldPrintDot_whenDotFormatIsSelected()
+{
+    let expectedOutput =
+r#"digraph "MachineDef" {
+    node [shape=box, style=rounded];
+    "[*]" [shape=point, label=""];
+    "[*]" -> "StartState";
+    "StartState" -> "TargetState" [label="on Event\nif &M::guard"];
+}
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format=dot", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldEscapeSpecialCharactersOnce_whenMermaidFormatIsSelected()
+{
+    let cppFileContent = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Busy, msmf::ActionSequence_<mpl::vector<Log<Start>, Say<"hi">>>, Check<3>>
+    > {};
+};
+"#;
+    let expectedOutput =
+"stateDiagram-v2
+    [*] --> Idle
+    Idle --> Busy : on Start<br>if Check#lt;3#gt;<br>do Log#lt;Start#gt;#59; Say#lt;#quot;hi#quot;#gt;
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["-f", "mermaid", "-"]).write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldPrintMermaid_whenMermaidFormatIsSelected()
+{
+    let expectedOutput =
+"stateDiagram-v2
+    [*] --> StartState
+    StartState --> TargetState : on Event<br>if &M::guard
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["-f", "mermaid", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
@@ -122,7 +122,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\ndo ActionSequence<>
+State1 --> State2 : on Event
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -288,7 +288,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\ndo ActionSequence<Action>
+State1 --> State2 : on Event\ndo Action
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -403,7 +403,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\ndo ActionSequence<Action<>>
+State1 --> State2 : on Event\ndo Action<>
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -534,7 +534,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\ndo ActionSequence<Action1, Action2>
+State1 --> State2 : on Event\ndo Action1; Action2
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
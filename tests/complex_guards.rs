@@ -149,7 +149,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\nif Not<>
+State1 --> State2 : on Event\nif !Guard
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -312,7 +312,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\nif Not<Guard>
+State1 --> State2 : on Event\nif !Guard
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -584,7 +584,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\nif Not<Guard<>>
+State1 --> State2 : on Event\nif !Guard<>
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -713,7 +713,7 @@ int main()
 r"@startuml
 hide empty description
 [*] --> State1
-State1 --> State2 : on Event\nif And<Guard1, Guard2>
+State1 --> State2 : on Event\nif Guard1 && Guard2
 @enduml
 ";
     assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(file.path()).assert().success()
@@ -0,0 +1,124 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, running_state, msmf::none, msmf::none>,
+        msmf::Row<running_state, Stop, Idle, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+const DOT_OUTPUT: &str =
+r#"digraph "MachineDef" {
+    node [shape=box, style=rounded];
+    "[*]" [shape=point, label=""];
+    "[*]" -> "Idle";
+    "Idle" -> "running_state" [label="on Start"];
+    "running_state" -> "Idle" [label="on Stop"];
+}
+"#;
+
+/// Creates a directory with the configuration file and a subdirectory with the C++ file, returning the path to the latter.
+fn makeProject(config: &str) -> (tempfile::TempDir, std::path::PathBuf)
+{
+    let directory = tempfile::tempdir().unwrap();
+    std::fs::write(directory.path().join("msm-grapher.toml"), config).unwrap();
+    std::fs::create_dir(directory.path().join("src")).unwrap();
+    let filePath = directory.path().join("src").join("machine.cpp");
+    std::fs::write(&filePath, CPP_FILE_CONTENT).unwrap();
+    (directory, filePath)
+}
+
+#[test]
+fn shouldUseOptionsFromConfigFile_foundAboveInputFile()
+{
+    let (_directory, filePath) = makeProject("format = \"dot\"\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(&filePath).assert().success()
+        .stdout(DOT_OUTPUT);
+}
+
+#[test]
+fn shouldPreferCommandLineOptions_overConfigFile()
+{
+    let (_directory, filePath) = makeProject("format = \"dot\"\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "mermaid"]).arg(&filePath).assert().success()
+        .stdout(predicates::str::starts_with("stateDiagram-v2\n"));
+}
+
+#[test]
+fn shouldIgnoreConfigFile_whenNoConfigIsGiven()
+{
+    let (_directory, filePath) = makeProject("format = \"dot\"\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--no-config").arg(&filePath).assert().success()
+        .stdout(predicates::str::starts_with("@startuml\n"));
+}
+
+#[test]
+fn shouldApplyTableOfCommand_onlyToThatCommand()
+{
+    let config = "format = \"dot\"\n\n[lint]\nstate-naming = \"^[A-Z]\"\n\n[lint.rules]\nmissing-initial-state = \"error\"\n";
+    let (_directory, filePath) = makeProject(config);
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("lint").arg(&filePath).assert().success()
+        .stdout(format!(
+            "{}:7: warning: state running_state in MachineDef does not match the naming pattern ^[A-Z] [naming]\n\
+             {}:8: note: running_state is also mentioned here\n\
+             Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n",
+            filePath.display(), filePath.display()));
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(&filePath).assert().success()
+        .stdout(DOT_OUTPUT);
+}
+
+#[test]
+fn shouldSkipTopLevelOptions_whichOnlyOtherCommandsHave()
+{
+    let (_directory, filePath) = makeProject("format = \"dot\"\nstate-naming = \"^[A-Z]\"\nport = 9000\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(&filePath).assert().success()
+        .stdout(DOT_OUTPUT);
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("lint").arg(&filePath).assert().success()
+        .stdout(predicates::str::contains("state running_state in MachineDef does not match the naming pattern ^[A-Z]"));
+}
+
+#[test]
+fn shouldMergeLintRules_fromConfigFileAndCommandLine()
+{
+    let (_directory, filePath) = makeProject("[lint.rules]\nnaming = \"error\"\nself-loop-without-action = \"off\"\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "--rule", "naming=off"]).arg(&filePath).assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldResolvePathsRelativeToConfigFile()
+{
+    let (directory, filePath) = makeProject("labels = \"labels.toml\"\n");
+    std::fs::write(directory.path().join("labels.toml"), "[labels]\nrunning_state = \"Running\"\n").unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(&filePath).assert().success()
+        .stdout(predicates::str::contains("state \"Running\" as running_state\n"));
+}
+
+#[test]
+fn shouldReadConfigFileGivenWithConfigOption()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let configPath = directory.path().join("diagrams.toml");
+    std::fs::write(&configPath, "format = \"dot\"\n").unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("--config").arg(&configPath).arg("-").write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(DOT_OUTPUT);
+}
+
+#[test]
+fn shouldFail_whenConfigFileHasUnknownOption()
+{
+    let (directory, filePath) = makeProject("colour = \"red\"\n");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(&filePath).assert().failure()
+        .stderr(format!("Error: \"{}: Unknown option: --colour, use --help to list available options.\"\n",
+            directory.path().join("msm-grapher.toml").display()));
+}
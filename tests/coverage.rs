@@ -0,0 +1,131 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::io::Write;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        _row<Busy, Stop, Idle>,
+        _row<Busy, Fail, states::Error>
+    > {};
+};
+"#;
+
+fn makeCppFile() -> tempfile::NamedTempFile
+{
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(CPP_FILE_CONTENT.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn shouldColourTransitionsByHitCount()
+{
+    let log = "\
+boot completed
+state=Idle event=Start -> state=Busy
+state=Busy event=Stop -> state=Idle
+state=Idle event=Start -> state=Busy
+";
+    let expectedOutput = r#"@startuml
+hide empty description
+state "states::Error" as states__Error
+[*] --> Idle
+Idle -[#green]-> Busy : on Start\n2 hit(s)
+Busy -[#green]-> Idle : on Stop\n1 hit(s)
+Busy -[#red]-> states__Error : on Fail\n0 hit(s)
+@enduml
+"#;
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["coverage", "--log", "-"]).arg(file.path())
+        .write_stdin(log)
+        .assert().success()
+        .stdout(expectedOutput)
+        .stderr("Coverage: 2 of 3 transitions taken (66.7%).\n");
+}
+
+#[test]
+fn shouldReportUnmatchedLogEntries()
+{
+    let log = "\
+state=Idle event=Start -> state=Busy
+state=Busy event=Explode -> state=Error
+state=Busy event=Fail -> state=Error
+";
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["coverage", "--log", "-"]).arg(file.path())
+        .write_stdin(log)
+        .assert().success()
+        .stderr("<stdin>:2: unmatched log entry: state=Busy event=Explode -> state=Error\nCoverage: 2 of 3 transitions taken (66.7%).\n");
+}
+
+#[test]
+fn shouldReportAmbiguousLogEntries_whenGuardedRowsShareStartEventAndTarget()
+{
+    let cppFileContent = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        g_row<Idle, Start, Busy, &MachineDef::isFast>,
+        g_row<Idle, Start, Busy, &MachineDef::isSlow>,
+        _row<Busy, Stop, Idle>
+    > {};
+};
+"#;
+    let log = "\
+state=Idle event=Start -> state=Busy
+state=Busy event=Stop -> state=Idle
+";
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(cppFileContent.as_bytes()).unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["coverage", "--log", "-"]).arg(file.path())
+        .write_stdin(log)
+        .assert().success()
+        .stdout(predicates::str::contains("Idle -[#red]-> Busy : on Start\\nif &MachineDef::isFast\\n0 hit(s)\n"))
+        .stdout(predicates::str::contains("Idle -[#red]-> Busy : on Start\\nif &MachineDef::isSlow\\n0 hit(s)\n"))
+        .stderr("<stdin>:1: ambiguous log entry matching 2 transitions: state=Idle event=Start -> state=Busy\n\
+            Coverage: 1 of 3 transitions taken (33.3%).\n");
+}
+
+#[test]
+fn shouldMatchLogEntries_withCustomPattern()
+{
+    let log = "[fsm] Busy --Stop--> Idle\n";
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["coverage", "--log", "-", "--log-pattern", r"\[fsm\] (?P<start>\w+) --(?P<event>\w+)-->"]).arg(file.path())
+        .write_stdin(log)
+        .assert().success()
+        .stdout(predicates::str::contains("Busy -[#green]-> Idle : on Stop\\n1 hit(s)\n"))
+        .stderr("Coverage: 1 of 3 transitions taken (33.3%).\n");
+}
+
+#[test]
+fn shouldFail_whenPatternLacksNamedGroups()
+{
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["coverage", "--log", "-", "--log-pattern", r"state=(\w+)"]).arg(file.path())
+        .write_stdin("")
+        .assert().failure()
+        .stderr("Error: \"Log pattern must contain named groups: start and event, and optionally: target.\"\n");
+}
+
+#[test]
+fn shouldFail_whenLogIsMissing()
+{
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("coverage").arg(file.path())
+        .assert().failure()
+        .stderr("Error: \"Command coverage requires a log file given with --log.\"\n");
+}
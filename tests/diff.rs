@@ -0,0 +1,110 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::io::Write;
+
+
+const OLD_CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>,
+        msmf::Row<Running, Pause, Paused, msmf::none, msmf::none>,
+        msmf::Row<Running, Stop, Idle, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+const NEW_CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Running, msmf::none, IsReady>,
+        msmf::Row<Running, Stop, Idle, Reset, msmf::none>,
+        msmf::Row<Running, Fail, Broken, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+fn makeFile(content: &str) -> tempfile::NamedTempFile
+{
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn shouldDescribeAddedRemovedAndChangedElements()
+{
+    let oldFile = makeFile(OLD_CPP_FILE_CONTENT);
+    let newFile = makeFile(NEW_CPP_FILE_CONTENT);
+    let expectedOutput =
+"Machine MachineDef:
+  + state Broken
+  - state Paused
+  + transition Running --> Broken : on Fail
+  - transition Running --> Paused : on Pause
+  ~ transition Idle --> Running : on Start, if IsReady
+      guard: (none) -> IsReady
+  ~ transition Running --> Idle : on Stop, do Reset
+      action: (none) -> Reset
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("diff").arg(oldFile.path()).arg(newFile.path())
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldReportNoDifferences_whenMachinesAreEqual()
+{
+    let oldFile = makeFile(OLD_CPP_FILE_CONTENT);
+    let newFile = makeFile(OLD_CPP_FILE_CONTENT);
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("diff").arg(oldFile.path()).arg(newFile.path())
+        .assert().success()
+        .stdout("No differences.\n");
+}
+
+#[test]
+fn shouldReadOldVersionFromStandardInput_whenPathIsDash()
+{
+    let newFile = makeFile(NEW_CPP_FILE_CONTENT.replace("MachineDef", "OtherDef").as_str());
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["diff", "-"]).arg(newFile.path())
+        .write_stdin(OLD_CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout("- machine MachineDef\n+ machine OtherDef\n");
+}
+
+#[test]
+fn shouldColorDifferences_whenHighlightIsRequested()
+{
+    let oldFile = makeFile(OLD_CPP_FILE_CONTENT);
+    let newFile = makeFile(NEW_CPP_FILE_CONTENT);
+    let expectedOutput =
+r"@startuml
+hide empty description
+state Broken #green
+state Paused #red
+[*] --> Idle
+Idle -[#orange]-> Running : on Start\nif IsReady
+Running -[#orange]-> Idle : on Stop\ndo Reset
+Running -[#green]-> Broken : on Fail
+Running -[#red]-> Paused : on Pause
+@enduml
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["diff", "--highlight"]).arg(oldFile.path()).arg(newFile.path())
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFail_whenOneFileIsGiven()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["diff", "-"]).assert().failure()
+        .stderr("Error: \"Command diff requires two files, e.g.: diff old.cpp new.cpp.\"\n");
+}
@@ -0,0 +1,75 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+fn makeCppFileContent(guard: &str) -> String
+{
+    format!(r#"
+#include <boost/msm/back/state_machine.hpp>
+#include <boost/msm/front/state_machine_def.hpp>
+#include <boost/msm/front/functor_row.hpp>
+#include <boost/msm/front/euml/operator.hpp>
+
+namespace msmf = boost::msm::front;
+using namespace boost::msm::front::euml;
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{{
+    using initial_state = State1;
+
+    struct transition_table : boost::mpl::vector<
+        msmf::Row<State1, Event, State2, msmf::none, {}>
+    > {{}};
+}};
+"#, guard)
+}
+
+fn makeExpectedOutput(guardText: &str) -> String
+{
+    format!("@startuml\nhide empty description\n[*] --> State1\nState1 --> State2 : on Event\\nif {}\n@enduml\n", guardText)
+}
+
+#[test]
+fn shouldRenderNegation_whenGuardIsNot()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(makeCppFileContent("Not_<IsEmpty>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("!IsEmpty"));
+}
+
+#[test]
+fn shouldRenderNestedCombinatorsInInfixNotation_whenGuardCombinesConjunctionDisjunctionAndNegation()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("And_<Not_<IsEmpty>, Or_<A, B>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("!IsEmpty && (A || B)"));
+}
+
+#[test]
+fn shouldParenthesizeNegatedConjunction_whenGuardIsQualified()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("msmf::euml::Not_<boost::msm::front::euml::And_<A, B>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("!(A && B)"));
+}
+
+#[test]
+fn shouldRenderFlatDisjunction_whenDisjunctionsAreNested()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(makeCppFileContent("Or_<Or_<A, B>, And_<C, D>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("A || B || C && D"));
+}
+
+#[test]
+fn shouldLeaveUnknownTemplatesAsTheyAre_whenTheyAreNotCombinators()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("And_<IsInRange<Limit>, Not_<>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("IsInRange<Limit> && Not_<>"));
+}
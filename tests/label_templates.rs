@@ -0,0 +1,89 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Running, StartMotor, IsReady>,
+        msmf::Row<Running, Stop, Idle, msmf::ActionSequence_<mpl::vector<StopMotor, Log>>, msmf::none>,
+        msmf::Row<Running, msmf::none, Idle, msmf::none, IsDone>
+    > {};
+};
+"#;
+
+const UML_TEMPLATE: &str = "{event}{ [guard]}{ / action}";
+
+#[test]
+fn shouldFormatLabelsWithTemplate_whenPlantUmlFormatIsSelected()
+{
+    let expectedOutput =
+r"@startuml
+hide empty description
+[*] --> Idle
+Idle --> Running : Start [IsReady] / StartMotor
+Running --> Idle : Stop / StopMotor; Log
+Running --> Idle : [IsDone]
+@enduml
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--label-template", UML_TEMPLATE, "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFormatLabelsWithTemplate_whenDotFormatIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "dot", "--label-template", "{event}{\\n[guard]}", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains("    \"Idle\" -> \"Running\" [label=\"Start\\n[IsReady]\"];\n"))
+        .stdout(predicates::str::contains("    \"Running\" -> \"Idle\" [label=\"[IsDone]\"];\n"));
+}
+
+#[test]
+fn shouldFormatLabelsWithTemplate_whenMermaidFormatIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "mermaid", "--label-template", UML_TEMPLATE, "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains("    Running --> Idle : Stop / StopMotor#59; Log\n"));
+}
+
+#[test]
+fn shouldHideGuardsAndActions_whenRequested()
+{
+    let expectedOutput =
+r"@startuml
+hide empty description
+[*] --> Idle
+Idle --> Running : on Start
+Running --> Idle : on Stop
+Running --> Idle
+@enduml
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--hide-guards", "--hide-actions", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFail_whenSectionOfTemplateNamesNoPart()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--label-template", "{event}{ (state)}", "-"]).assert().failure()
+        .stderr("Error: \"Section { (state)} of the label template should contain one of: event, guard, action.\"\n");
+}
+
+#[test]
+fn shouldFail_whenSectionOfTemplateIsNotClosed()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--label-template", "{event", "-"]).assert().failure()
+        .stderr("Error: \"Label template has an unclosed section: {event\"\n");
+}
@@ -0,0 +1,205 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use serde_json::{Value, json};
+
+
+const URI: &str = "file:///machine.cpp";
+const CPP_FILE_CONTENT: &str = r#"
+struct Idle : msmf::state<> {};
+struct Busy : msmf::state<> {};
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Busy, msmf::none, msmf::none>,
+        msmf::Row<Busy, Stop, Idle, Reset, msmf::none>,
+        msmf::Row<Lost, Stop, Idle, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+fn frame(message: Value) -> String
+{
+    let content = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+}
+
+fn makeInput(messages: Vec<Value>) -> String
+{
+    let mut messages: Vec<Value> = [
+        json!({"jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {"capabilities": {}}}),
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": {"textDocument": {"uri": URI, "languageId": "cpp", "version": 1, "text": CPP_FILE_CONTENT}}})
+    ].into_iter().chain(messages).collect();
+    messages.push(json!({"jsonrpc": "2.0", "id": 99, "method": "shutdown"}));
+    messages.push(json!({"jsonrpc": "2.0", "method": "exit"}));
+    messages.into_iter().map(frame).collect()
+}
+
+/// Runs the server with the messages sent after opening the document and returns the messages it sent.
+fn runServer(messages: Vec<Value>) -> Vec<Value>
+{
+    let output = assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lsp", "--no-config"])
+        .write_stdin(makeInput(messages))
+        .assert().success()
+        .get_output().stdout.clone();
+    let mut output = String::from_utf8(output).unwrap();
+    let mut messages = vec![];
+    while let Some((header, rest)) = output.split_once("\r\n\r\n") {
+        let length: usize = header.strip_prefix("Content-Length: ").unwrap().parse().unwrap();
+        messages.push(serde_json::from_str(&rest[..length]).unwrap());
+        output = rest[length..].to_string();
+    }
+    messages
+}
+
+fn findResponse(messages: &[Value], id: i64) -> &Value
+{
+    messages.iter().find(|message| message["id"] == id).unwrap()
+}
+
+fn findLastDiagnostics(messages: &[Value]) -> &Value
+{
+    &messages.iter().rfind(|message| message["method"] == "textDocument/publishDiagnostics").unwrap()["params"]["diagnostics"]
+}
+
+#[test]
+fn shouldPublishAnalysisFindings_whenDocumentIsOpened()
+{
+    let messages = runServer(vec![]);
+    assert_eq!(findResponse(&messages, 0)["result"]["capabilities"]["hoverProvider"], true);
+    let diagnostics = findLastDiagnostics(&messages);
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["message"], "state Lost in MachineDef is unreachable from the initial state");
+    assert_eq!(diagnostics[0]["severity"], 2);
+    assert_eq!(diagnostics[0]["range"], json!({"start": {"line": 11, "character": 8}, "end": {"line": 11, "character": 59}}));
+}
+
+#[test]
+fn shouldPublishSyntaxErrorAtItsToken_whenIncrementalChangeBreaksRow()
+{
+    let change = json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+        "textDocument": {"uri": URI, "version": 2},
+        "contentChanges": [{"range": {"start": {"line": 10, "character": 28}, "end": {"line": 10, "character": 29}}, "text": ""}]
+    }});
+    let messages = runServer(vec![change]);
+    let diagnostics = findLastDiagnostics(&messages);
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["range"], json!({"start": {"line": 10, "character": 29}, "end": {"line": 10, "character": 33}}));
+    assert!(diagnostics[0]["message"].as_str().unwrap().starts_with("Expected comma after event"));
+}
+
+#[test]
+fn shouldMoveFindingsOfFollowingTables_whenRowIsInsertedIntoTable()
+{
+    let text = format!("{}{}", CPP_FILE_CONTENT, r#"
+struct OtherDef : public msmf::state_machine_def<OtherDef>
+{
+    struct transition_table : mpl::vector<
+        msmf::Row<Open, Close, Closed, msmf::none, msmf::none>,
+        msmf::Row<Closed, Open, Open, msmf::none, msmf::none>,
+        msmf::Row<Broken, Open, Open, msmf::none, msmf::none>
+    > {};
+};
+"#);
+    let replacement = json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+        "textDocument": {"uri": URI, "version": 2},
+        "contentChanges": [{"text": text}]
+    }});
+    let insertion = json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+        "textDocument": {"uri": URI, "version": 3},
+        "contentChanges": [
+            {"range": {"start": {"line": 10, "character": 0}, "end": {"line": 10, "character": 0}},
+                "text": "        msmf::Row<Busy, Pause, Busy, msmf::none, msmf::none>,\n"},
+            {"range": {"start": {"line": 10, "character": 24}, "end": {"line": 10, "character": 29}}, "text": "Wait"}
+        ]
+    }});
+    let hover = json!({"jsonrpc": "2.0", "id": 1, "method": "textDocument/hover",
+        "params": {"textDocument": {"uri": URI}, "position": {"line": 10, "character": 20}}});
+    let messages = runServer(vec![replacement, insertion, hover]);
+    let diagnostics = findLastDiagnostics(&messages);
+    assert_eq!(diagnostics.as_array().unwrap().len(), 2);
+    assert_eq!(diagnostics[0]["message"], "state Lost in MachineDef is unreachable from the initial state");
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 12);
+    assert_eq!(diagnostics[1]["message"], "state Broken in OtherDef is unreachable from the initial state");
+    assert_eq!(diagnostics[1]["range"]["start"]["line"], 21);
+    let hoverText = findResponse(&messages, 1)["result"]["contents"]["value"].as_str().unwrap();
+    assert!(hoverText.contains("- `Busy --> Busy : on Wait`"), "{}", hoverText);
+}
+
+#[test]
+fn shouldListTransitionsOfState_whenHoveringOverIt()
+{
+    let hover = json!({"jsonrpc": "2.0", "id": 1, "method": "textDocument/hover",
+        "params": {"textDocument": {"uri": URI}, "position": {"line": 9, "character": 20}}});
+    let messages = runServer(vec![hover]);
+    let result = &findResponse(&messages, 1)["result"];
+    assert_eq!(result["contents"]["value"], "\
+State **Idle** in MachineDef
+
+Incoming:
+- `Busy --> Idle : on Stop, do Reset`
+- `Lost --> Idle : on Stop`
+
+Outgoing:
+- `Idle --> Busy : on Start`");
+    assert_eq!(result["range"], json!({"start": {"line": 9, "character": 18}, "end": {"line": 9, "character": 22}}));
+}
+
+#[test]
+fn shouldGoToStructOfState_whenDefinitionIsRequestedInRow()
+{
+    let definition = json!({"jsonrpc": "2.0", "id": 1, "method": "textDocument/definition",
+        "params": {"textDocument": {"uri": URI}, "position": {"line": 9, "character": 32}}});
+    let messages = runServer(vec![definition]);
+    assert_eq!(findResponse(&messages, 1)["result"],
+        json!({"uri": URI, "range": {"start": {"line": 2, "character": 7}, "end": {"line": 2, "character": 11}}}));
+}
+
+#[test]
+fn shouldRenderDiagrams_whenRenderIsRequested()
+{
+    let render = json!({"jsonrpc": "2.0", "id": 1, "method": "msm-grapher/render",
+        "params": {"textDocument": {"uri": URI}, "format": "mermaid"}});
+    let unknownFormat = json!({"jsonrpc": "2.0", "id": 2, "method": "msm-grapher/render",
+        "params": {"textDocument": {"uri": URI}, "format": "png"}});
+    let svg = json!({"jsonrpc": "2.0", "id": 3, "method": "msm-grapher/render",
+        "params": {"textDocument": {"uri": URI}, "format": "svg"}});
+    let messages = runServer(vec![render, unknownFormat, svg]);
+    let diagrams = &findResponse(&messages, 1)["result"]["diagrams"];
+    assert_eq!(diagrams[0]["machine"], "MachineDef");
+    assert_eq!(diagrams[0]["text"], "\
+stateDiagram-v2
+    [*] --> Idle
+    Idle --> Busy : on Start
+    Busy --> Idle : on Stop<br>do Reset
+    Lost --> Idle : on Stop");
+    assert_eq!(findResponse(&messages, 2)["error"]["code"], -32602);
+    let svgText = findResponse(&messages, 3)["result"]["diagrams"][0]["text"].as_str().unwrap();
+    assert!(svgText.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""), "{}", svgText);
+    assert!(svgText.contains(">on Start</tspan>"), "{}", svgText);
+}
+
+#[test]
+fn shouldFail_whenMessageIsTooLong()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lsp", "--no-config"])
+        .write_stdin("Content-Length: 1000000000000\r\n\r\n{}")
+        .assert().failure()
+        .stderr("Error: \"Message is longer than the limit of 67108864 bytes: 1000000000000\"\n");
+}
+
+#[test]
+fn shouldFail_whenPathIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lsp", "machine.cpp"])
+        .assert().failure()
+        .stderr("Error: \"Command lsp receives documents from the editor, so it does not take paths.\"\n");
+}
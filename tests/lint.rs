@@ -0,0 +1,115 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+fn makeCppFileContent(rows: &str) -> String
+{
+    format!(r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+{}
+    > {{}};
+}};
+"#, rows)
+}
+
+#[test]
+fn shouldReportNothing_whenRulesAreFollowed()
+{
+    let rows = "        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>,
+        msmf::Row<Running, Stop, Idle, Reset, msmf::none>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "-"]).write_stdin(makeCppFileContent(rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldReportViolatedRules()
+{
+    let rows = "        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>,
+        msmf::Row<Running, stop_event, Idle, Reset, msmf::none>,
+        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>,
+        msmf::Row<Idle, Tick, Idle, msmf::none, msmf::none>,
+        msmf::Row<Waiting, msmf::none, Idle, msmf::none, msmf::none>,
+        msmf::Row<Idle, msmf::none, Waiting, msmf::none, IsReady>";
+    let expectedOutput =
+"<stdin>:8: warning: event stop_event in MachineDef does not match the naming pattern ^[A-Z][A-Za-z0-9]*$ [naming]
+<stdin>:9: warning: rows starting in Idle in MachineDef are not kept together [unsorted-rows]
+<stdin>:7: note: previous row starting in Idle
+<stdin>:9: warning: duplicate row from Idle on Start to Running in MachineDef [duplicate-row]
+<stdin>:7: note: first declared here
+<stdin>:10: warning: transition from Idle to itself on Tick in MachineDef has no action [self-loop-without-action]
+<stdin>:11: warning: anonymous transition from Waiting to Idle in MachineDef has no guard and may loop forever [unguarded-none-event]
+<stdin>:12: warning: rows starting in Idle in MachineDef are not kept together [unsorted-rows]
+<stdin>:10: note: previous row starting in Idle
+Summary: 0 error(s) and 6 warning(s) in 1 machine(s).
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "-"]).write_stdin(makeCppFileContent(rows))
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldReportMissingInitialState()
+{
+    let fileContent = makeCppFileContent("        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>")
+        .replace("    using initial_state = Idle;\n", "");
+    let expectedOutput =
+"<stdin>:6: warning: MachineDef does not declare initial_state, the start of the first row Idle is assumed [missing-initial-state]
+Summary: 0 error(s) and 1 warning(s) in 1 machine(s).
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "-"]).write_stdin(fileContent)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldSkipFinding_whenRowAllowsRuleInComment()
+{
+    let rows = "        // msm-grapher: allow(self-loop-without-action)
+        msmf::Row<Idle, Tick, Idle, msmf::none, msmf::none>,
+        msmf::Row<Idle, Tock, Idle, msmf::none, msmf::none>, // msm-grapher: allow(naming, self-loop-without-action)
+        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "-"]).write_stdin(makeCppFileContent(rows))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldFail_whenRuleHasErrorSeverity()
+{
+    let rows = "        msmf::Row<idle, Start, Running, msmf::none, msmf::none>";
+    let expectedOutput =
+"<stdin>:7: error: state idle in MachineDef does not match the naming pattern ^[A-Z][A-Za-z0-9]*$ [naming]
+Summary: 1 error(s) and 0 warning(s) in 1 machine(s).
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "--rule", "naming=error", "-"])
+        .write_stdin(makeCppFileContent(rows).replace("initial_state = Idle", "initial_state = idle"))
+        .assert().failure()
+        .stdout(expectedOutput)
+        .stderr("Error: \"Found 1 error(s).\"\n");
+}
+
+#[test]
+fn shouldUseGivenNamingPatternsAndSkipDisabledRules()
+{
+    let rows = "        msmf::Row<idle_state, start_event, idle_state, msmf::none, msmf::none>";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["lint", "--state-naming", "_state$", "--event-naming=_event$", "--rule", "self-loop-without-action=off", "-"])
+        .write_stdin(makeCppFileContent(rows).replace("initial_state = Idle", "initial_state = idle_state"))
+        .assert().success()
+        .stdout("Summary: 0 error(s) and 0 warning(s) in 1 machine(s).\n");
+}
+
+#[test]
+fn shouldFail_whenUnknownRuleIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["lint", "--rule", "spelling=off", "-"]).assert().failure()
+        .stderr("Error: \"Unknown lint rule: spelling, expected one of: naming, unsorted-rows, duplicate-row, \
+self-loop-without-action, unguarded-none-event, missing-initial-state.\"\n");
+}
@@ -0,0 +1,90 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        row<Busy, Stop, Idle, Log, IsDone>,
+        _row<Busy, Stop, Failed>,
+        a_row<Failed, Reset, Idle, ActionSequence_<mpl::vector<Clear, Notify>>>
+    > {};
+};
+"#;
+
+#[test]
+fn shouldRenderMarkdownMatrix_whenMatrixFormatIsSelected()
+{
+    let expectedOutput = "\
+| MachineDef | Start | Stop | Reset |
+| --- | --- | --- | --- |
+| Idle | Busy | | |
+| Busy | | Idle [IsDone] / Log<br>Failed | |
+| Failed | | | Idle / Clear; Notify |
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "matrix", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldRenderCsvMatrix_whenCsvTableFormatIsSelected()
+{
+    let expectedOutput = "\
+MachineDef,Start,Stop,Reset
+Idle,Busy,,
+Busy,,\"Idle [IsDone] / Log
+Failed\",
+Failed,,,Idle / Clear; Notify
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "matrix", "--table-format", "csv", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldHighlightUnhandledCells_whenHtmlTableFormatIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format=matrix", "--table-format=html", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains(concat!(
+            "  <tr><th>Failed</th><td style=\"background-color: #fdd\"></td><td style=\"background-color: #fdd\"></td>",
+            "<td>Idle / Clear; Notify</td></tr>\n")))
+        .stdout(predicates::str::contains("<td>Idle [IsDone] / Log<br>Failed</td>"));
+}
+
+#[test]
+fn shouldShowInternalTransitionInRowOfItsStartState()
+{
+    let expectedOutput = "\
+| MachineDef | Start | Stop | Tick | Reset |
+| --- | --- | --- | --- | --- |
+| Idle | Busy | | | |
+| Busy | | Idle [IsDone] / Log<br>Failed | (internal) [IsCounting] / Count | |
+| Failed | | | | Idle / Clear; Notify |
+";
+    let cppFileContent = CPP_FILE_CONTENT.replace("_row<Busy, Stop, Failed>,",
+        "_row<Busy, Stop, Failed>,\n        Row<Busy, Tick, none, Count, IsCounting>,");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--format", "matrix", "-"])
+        .write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFail_whenTableFormatIsUsedWithoutMatrixFormat()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--table-format", "csv", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().failure()
+        .stderr("Error: \"Option --table-format requires --format matrix.\"\n");
+}
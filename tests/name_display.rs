@@ -0,0 +1,139 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+#include <boost/msm/back/state_machine.hpp>
+#include <boost/msm/front/state_machine_def.hpp>
+#include <boost/msm/front/functor_row.hpp>
+#include <boost/msm/front/euml/operator.hpp>
+
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = my::ns::Idle;
+
+    struct transition_table : boost::mpl::vector<
+        boost::msm::front::Row<my::ns::Idle, my::events::Start, my::ns::Busy, boost::msm::front::none,
+            boost::msm::front::euml::And_<my::ns::IsReady, my::ns::Check<my::ns::HasData>>>
+    > {};
+};
+"#;
+
+#[test]
+fn shouldShowFullNames_whenNoNamePolicyIsProvided()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "my::ns::Idle" as my__ns__Idle
+state "my::ns::Busy" as my__ns__Busy
+[*] --> my__ns__Idle
+my__ns__Idle --> my__ns__Busy : on my::events::Start\nif my::ns::IsReady && my::ns::Check<my::ns::HasData>
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(CPP_FILE_CONTENT).assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldStripAllNamespaces_whenStripNamespacesOptionIsProvided()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "Idle" as my__ns__Idle
+state "Busy" as my__ns__Busy
+[*] --> my__ns__Idle
+my__ns__Idle --> my__ns__Busy : on Start\nif IsReady && Check<HasData>
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--strip-namespaces", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldStripOnlyConfiguredNamespaces_whenNamespacePrefixesAreProvided()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "Idle" as my__ns__Idle
+state "Busy" as my__ns__Busy
+[*] --> my__ns__Idle
+my__ns__Idle --> my__ns__Busy : on my::events::Start\nif IsReady && Check<HasData>
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["--strip-namespace", "my::ns", "--strip-namespace", "boost::msm::front::", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldCollapseNestedTemplateArguments_whenTemplateDepthIsProvided()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "Idle" as my__ns__Idle
+state "Busy" as my__ns__Busy
+[*] --> my__ns__Idle
+my__ns__Idle --> my__ns__Busy : on Start\nif IsReady && Check<...>
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--strip-namespaces", "--template-depth", "0", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldShowLabels_whenLabelsFileIsProvided()
+{
+    let labelsFile = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(labelsFile.path(), "[labels]\n\"my::ns::Idle\" = \"Waiting for user\"\nIsReady = \"ready\"\n").unwrap();
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "Waiting for user" as my__ns__Idle
+state "Busy" as my__ns__Busy
+[*] --> my__ns__Idle
+my__ns__Idle --> my__ns__Busy : on Start\nif ready && Check<HasData>
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--strip-namespaces", "--labels"]).arg(labelsFile.path()).arg("-")
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldApplyNamePolicyToDot_whenDotFormatIsSelected()
+{
+    let expectedOutput =
+r#"digraph "MachineDef" {
+    node [shape=box, style=rounded];
+    "[*]" [shape=point, label=""];
+    "my::ns::Idle" [label="Idle"];
+    "my::ns::Busy" [label="Busy"];
+    "[*]" -> "my::ns::Idle";
+    "my::ns::Idle" -> "my::ns::Busy" [label="on Start\nif IsReady && Check<...>"];
+}
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["--strip-namespaces", "--template-depth", "0", "--format", "dot", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFail_whenTemplateDepthIsNotANumber()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--template-depth", "deep", "-"]).assert().failure()
+        .stderr("Error: \"Option --template-depth requires a non-negative number, got: deep.\"\n");
+}
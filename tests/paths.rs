@@ -0,0 +1,143 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        row<Busy, Check, Verifying, Log, IsReady>,
+        _row<Verifying, Fail, Error>,
+        _row<Busy, Fail, Error>,
+        _row<Error, Reset, Idle>
+    > {};
+};
+"#;
+
+#[test]
+fn shouldPrintShortestPath()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Idle", "Verifying", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(concat!(
+            "Shortest path from Idle to Verifying in MachineDef:\n",
+            "  Idle --> Busy : on Start\n",
+            "  Busy --> Verifying : on Check, if IsReady, do Log\n"));
+}
+
+#[test]
+fn shouldPrintCycle_whenStartAndTargetStatesAreTheSame()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Busy", "Busy", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(concat!(
+            "Shortest path from Busy to Busy in MachineDef:\n",
+            "  Busy --> Error : on Fail\n",
+            "  Error --> Idle : on Reset\n",
+            "  Idle --> Busy : on Start\n"));
+}
+
+#[test]
+fn shouldPrintAllSimplePathsUpToLength_whenAllPathsOptionIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "--all-paths", "3", "Idle", "Error", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(concat!(
+            "Path 1 from Idle to Error in MachineDef:\n",
+            "  Idle --> Busy : on Start\n",
+            "  Busy --> Error : on Fail\n",
+            "\n",
+            "Path 2 from Idle to Error in MachineDef:\n",
+            "  Idle --> Busy : on Start\n",
+            "  Busy --> Verifying : on Check, if IsReady, do Log\n",
+            "  Verifying --> Error : on Fail\n"));
+}
+
+#[test]
+fn shouldSkipLongerPaths_whenTheyExceedLength()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "--all-paths=2", "Idle", "Error", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(concat!(
+            "Path 1 from Idle to Error in MachineDef:\n",
+            "  Idle --> Busy : on Start\n",
+            "  Busy --> Error : on Fail\n"));
+}
+
+#[test]
+fn shouldHighlightPathInDiagram_whenHighlightOptionIsProvided()
+{
+    let expectedOutput = r"@startuml
+hide empty description
+state Idle #red
+state Busy #red
+state Error #red
+[*] --> Idle
+Idle -[#red]-> Busy : on Start
+Busy --> Verifying : on Check\nif IsReady\ndo Log
+Verifying --> Error : on Fail
+Busy -[#red]-> Error : on Fail
+Error --> Idle : on Reset
+@enduml
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "--highlight", "Idle", "Error", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldFail_whenThereIsNoPath()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Verifying", "Verifying", "-"])
+        .write_stdin(CPP_FILE_CONTENT.replace("_row<Error, Reset, Idle>", "_row<Error, Reset, Error>"))
+        .assert().failure()
+        .stderr("Error: \"No path from Verifying to Verifying was found in MachineDef.\"\n");
+}
+
+#[test]
+fn shouldFail_whenStateIsUnknown()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Idle", "Unknown", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().failure()
+        .stderr("Error: \"States Idle and Unknown were not found together in any transition table.\"\n");
+}
+
+#[test]
+fn shouldSkipInternalTransitions_andRejectNoneAsState()
+{
+    let cppFileContent = CPP_FILE_CONTENT.replace("_row<Busy, Fail, Error>", "_row<Busy, Fail, Error>,\n        Row<Busy, Tick, none, Count, none>");
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Busy", "Busy", "-"])
+        .write_stdin(cppFileContent.as_str())
+        .assert().success()
+        .stdout(concat!(
+            "Shortest path from Busy to Busy in MachineDef:\n",
+            "  Busy --> Error : on Fail\n",
+            "  Error --> Idle : on Reset\n",
+            "  Idle --> Busy : on Start\n"));
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Busy", "none", "-"])
+        .write_stdin(cppFileContent)
+        .assert().failure()
+        .stderr("Error: \"States Busy and none were not found together in any transition table.\"\n");
+}
+
+#[test]
+fn shouldFail_whenStatesAreMissing()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["path", "Idle", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().failure()
+        .stderr(concat!(
+            "Error: \"Command path requires a start state, a target state and a file, e.g.: ",
+            "path Idle Error machine.cpp.\"\n"));
+}
@@ -0,0 +1,110 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::io::Write;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = ui::Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<ui::Idle, Start, net::Connecting, msmf::none, msmf::none>,
+        msmf::Row<net::Connecting, Fail, ui::ErrorScreen, msmf::none, msmf::none>,
+        msmf::Row<ui::ErrorScreen, Start, net::Connecting, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+#[test]
+fn shouldApplyThemeSkinparamsAndDirection()
+{
+    let expectedOutput =
+r#"@startuml
+!theme plain
+hide empty description
+left to right direction
+skinparam monochrome true
+skinparam shadowing false
+state "ui::Idle" as ui__Idle
+state "net::Connecting" as net__Connecting
+state "ui::ErrorScreen" as ui__ErrorScreen
+[*] --> ui__Idle
+ui__Idle --> net__Connecting : on Start
+net__Connecting --> ui__ErrorScreen : on Fail
+ui__ErrorScreen --> net__Connecting : on Start
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["--theme", "plain", "--direction", "left-right", "--skinparam", "monochrome=true", "--skinparam=shadowing=false", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldColorStatesByNamespaceAndPattern_andTransitionsByEvent()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "ui::Idle" as ui__Idle #LightBlue
+state "net::Connecting" as net__Connecting #Orange
+state "ui::ErrorScreen" as ui__ErrorScreen #red
+[*] --> ui__Idle
+ui__Idle --> net__Connecting : on Start
+net__Connecting -[#red]-> ui__ErrorScreen : on Fail
+ui__ErrorScreen --> net__Connecting : on Start
+legend right
+Events:
+Start
+<color:#red>Fail</color>
+endlegend
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .args(["--color-namespace", "ui=LightBlue", "--color-namespace", "::net=#Orange", "--color-states", "Error.*$=red"])
+        .args(["--color-event", "Fail=red", "--legend", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldInsertHeaderAndFooterFiles()
+{
+    let mut header = tempfile::NamedTempFile::new().unwrap();
+    header.write_all(b"title Connection").unwrap();
+    let mut footer = tempfile::NamedTempFile::new().unwrap();
+    footer.write_all(b"note \"Generated\" as N1\n").unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap()
+        .arg("--header").arg(header.path()).arg("--footer").arg(footer.path()).arg("-")
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::starts_with("@startuml\nhide empty description\ntitle Connection\nstate \"ui::Idle\""))
+        .stdout(predicates::str::ends_with("on Start\nnote \"Generated\" as N1\n@enduml\n"));
+}
+
+#[test]
+fn shouldReadStylingFromConfigTables()
+{
+    let directory = tempfile::tempdir().unwrap();
+    std::fs::write(directory.path().join("msm-grapher.toml"),
+        "direction = \"left-right\"\n\n[skinparams]\nmonochrome = \"true\"\n\n[event-colors]\nFail = \"red\"\n").unwrap();
+    let filePath = directory.path().join("machine.cpp");
+    std::fs::write(&filePath, CPP_FILE_CONTENT).unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg(&filePath)
+        .assert().success()
+        .stdout(predicates::str::starts_with("@startuml\nhide empty description\nleft to right direction\nskinparam monochrome true\n"))
+        .stdout(predicates::str::contains("net__Connecting -[#red]-> ui__ErrorScreen : on Fail\n"));
+}
+
+#[test]
+fn shouldFail_whenUnknownDirectionIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--direction", "diagonal", "-"]).assert().failure()
+        .stderr("Error: \"Unknown layout direction: diagonal, expected one of: top-down, left-right.\"\n");
+}
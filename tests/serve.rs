@@ -0,0 +1,135 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Stdio};
+use std::time::Duration;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>,
+        msmf::Row<Running, stop, Idle, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+/// Kills the server when a test ends, also when it fails.
+struct Server
+{
+    process: Child,
+    address: String
+}
+
+impl Server
+{
+    fn start(path: &std::path::Path) -> Self
+    {
+        let mut process = std::process::Command::new(assert_cmd::cargo::cargo_bin(APP_NAME))
+            .args(["serve", "--no-config", "--port", "0"]).arg(path)
+            .stdout(Stdio::piped())
+            .spawn().unwrap();
+        let mut line = String::new();
+        BufReader::new(process.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let address = line.split("http://").nth(1).unwrap().split('/').next().unwrap().to_string();
+        Self{process, address}
+    }
+
+    fn connect(&self, target: &str) -> TcpStream
+    {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", target, self.address).unwrap();
+        stream
+    }
+
+    fn get(&self, target: &str) -> String
+    {
+        let mut response = String::new();
+        self.connect(target).read_to_string(&mut response).unwrap();
+        response
+    }
+}
+
+impl Drop for Server
+{
+    fn drop(&mut self)
+    {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+#[test]
+fn shouldServePageWithDiagramsAndLintFindings()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("machine.cpp");
+    std::fs::write(&path, CPP_FILE_CONTENT).unwrap();
+    let server = Server::start(&path);
+
+    let response = server.get("/");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\n"), "{}", response);
+    assert!(response.contains("<li><a href=\"#machine-0\">MachineDef</a>"), "{}", response);
+    assert!(response.contains("<li class=\"warning\">line 8: event stop in MachineDef does not match the naming pattern"),
+        "{}", response);
+    assert!(response.contains("<svg xmlns=\"http://www.w3.org/2000/svg\""), "{}", response);
+    assert!(response.contains("<title>MachineDef</title>"), "{}", response);
+    assert!(response.contains(">on Start</tspan>"), "{}", response);
+    assert!(response.contains("new EventSource(\"/events\")"), "{}", response);
+
+    assert!(server.get("/missing").starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[test]
+fn shouldGiveArrowMarkersUniqueIds_whenPageShowsSeveralMachines()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("machines.cpp");
+    std::fs::write(&path, CPP_FILE_CONTENT.to_string() + &CPP_FILE_CONTENT.replace("MachineDef", "OtherMachineDef")).unwrap();
+    let server = Server::start(&path);
+
+    let response = server.get("/");
+    for idPrefix in ["machine-0", "machine-1"] {
+        assert!(response.contains(&format!("<marker id=\"{}-arrow0\"", idPrefix)), "{}", response);
+        assert!(response.contains(&format!("marker-end=\"url(#{}-arrow0)\"", idPrefix)), "{}", response);
+    }
+}
+
+#[test]
+fn shouldSendReloadEvent_whenFileChanges()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("machine.cpp");
+    std::fs::write(&path, CPP_FILE_CONTENT).unwrap();
+    let server = Server::start(&path);
+
+    let mut events = BufReader::new(server.connect("/events"));
+    let mut line = String::new();
+    events.read_line(&mut line).unwrap();
+    assert_eq!(line, "HTTP/1.1 200 OK\r\n");
+    while line != "\r\n" {
+        line.clear();
+        events.read_line(&mut line).unwrap();
+    }
+
+    std::fs::write(&path, CPP_FILE_CONTENT.replace("stop", "Stop")).unwrap();
+    line.clear();
+    events.read_line(&mut line).unwrap();
+    assert_eq!(line, "data: reload\n");
+}
+
+#[test]
+fn shouldFail_whenServingStandardInput()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["serve", "-"])
+        .assert().failure()
+        .stderr("Error: \"Command serve reads files again when they change, so they cannot be read from standard input.\"\n");
+}
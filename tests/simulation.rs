@@ -0,0 +1,130 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use predicates::prelude::PredicateBooleanExt;
+use std::io::Write;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        row<Busy, Check, Done, Log, IsReady>,
+        row<Busy, Check, Failed, none, And_<IsBroken, Not_<IsReady>>>,
+        _row<Done, none, Idle>
+    > {};
+};
+"#;
+
+fn makeCppFile() -> tempfile::NamedTempFile
+{
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(CPP_FILE_CONTENT.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn shouldProcessEvents_andAskAboutGuards()
+{
+    let expectedOutput = "\
+Simulating MachineDef. Type an event name or help to list commands.
+Current state: Idle
+> Candidates from Idle on Start:
+  row at line 7: Idle --> Busy
+Idle --> Busy
+Current state: Busy
+> Candidates from Busy on Check:
+  row at line 8: Busy --> Done if IsReady
+  row at line 9: Busy --> Failed if IsBroken && !IsReady
+Does guard IsBroken hold? [y/n] Does guard IsReady hold? [y/n] Executing action: Log
+Busy --> Done
+Done --> Idle
+Current state: Idle
+> 1. Start: Idle --> Busy
+2. Check: Busy --> Done, Done --> Idle
+> ";
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("simulate").arg(file.path())
+        .write_stdin("Start\nCheck\nn\ny\nhistory\nquit\n")
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldUsePresetGuardAnswers_whenGuardOptionsAreProvided()
+{
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["simulate", "--guard", "IsBroken=true", "--guard=IsReady=false"])
+        .arg(file.path())
+        .write_stdin("Start\nCheck\n")
+        .assert().success()
+        .stdout(predicates::str::contains("Busy --> Failed\nCurrent state: Failed\n"))
+        .stdout(predicates::str::contains("Does guard").not());
+}
+
+#[test]
+fn shouldRestorePreviousState_whenUndoIsTyped()
+{
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("simulate").arg(file.path())
+        .write_stdin("Start\nundo\nundo\nstate\nevents\n")
+        .assert().success()
+        .stdout(predicates::str::contains(concat!(
+            "> Undone Start.\nCurrent state: Idle\n",
+            "> Nothing to undo.\n",
+            "> Current state: Idle\n",
+            "> Handled events: Start\n")));
+}
+
+#[test]
+fn shouldReportUnknownEvent()
+{
+    let file = makeCppFile();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("simulate").arg(file.path())
+        .write_stdin("Stop\nCheck\n")
+        .assert().success()
+        .stdout(predicates::str::contains("> Unknown event: Stop, type events to list the handled ones.\n"))
+        .stdout(predicates::str::contains("> No transition from Idle on Check.\nCurrent state: Idle\n"));
+}
+
+#[test]
+fn shouldStayInCurrentState_whenTransitionIsInternal()
+{
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(br#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        Row<Busy, Tick, none, Count, none>,
+        _row<Busy, Stop, Idle>
+    > {};
+};
+"#).unwrap();
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("simulate").arg(file.path())
+        .write_stdin("Start\nTick\nStop\n")
+        .assert().success()
+        .stdout(predicates::str::contains(concat!(
+            "> Candidates from Busy on Tick:\n",
+            "  row at line 8: Busy (internal)\n",
+            "Executing action: Count\n",
+            "Busy (internal)\n",
+            "Current state: Busy\n",
+            "> Candidates from Busy on Stop:\n")))
+        .stdout(predicates::str::contains("Busy --> Idle\nCurrent state: Idle\n"));
+}
+
+#[test]
+fn shouldFail_whenFileIsStandardInput()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["simulate", "-"])
+        .assert().failure()
+        .stderr("Error: \"Command simulate reads events from standard input, so the file cannot be read from it.\"\n");
+}
@@ -0,0 +1,125 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct Idle : public msmf::state<>
+{
+    template <class Event, class Fsm>
+    void on_entry(Event const&, Fsm& fsm)
+    {
+        // Waits for the user.
+        fsm.startTimer(5);
+        log::info("idle()");
+        if (fsm.isReady()) { notify<Ui>(); }
+    }
+
+    struct Helper { void on_exit() { hidden(); } };
+
+    template <class Event, class Fsm>
+    void on_exit(Event const&, Fsm&) const
+    {
+    }
+};
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Busy, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+fn makeExpectedOutput(entryDescription: &str) -> String
+{
+    format!("@startuml\nhide empty description\nIdle : {}\nIdle : exit /\n[*] --> Idle\nIdle --> Busy : on Start\n@enduml\n",
+        entryDescription)
+}
+
+#[test]
+fn shouldNotShowStateBehaviors_byDefault()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout("@startuml\nhide empty description\n[*] --> Idle\nIdle --> Busy : on Start\n@enduml\n");
+}
+
+#[test]
+fn shouldListEntryAndExit_whenPlainDetailIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--state-behaviors", "plain", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(makeExpectedOutput("entry /"));
+}
+
+#[test]
+fn shouldShowFirstCommentLine_whenCommentDetailIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--state-behaviors", "comment", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(makeExpectedOutput("entry / Waits for the user."));
+}
+
+#[test]
+fn shouldShowCalledFunctions_whenCallsDetailIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--state-behaviors", "calls", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(makeExpectedOutput("entry / fsm.startTimer; log::info; fsm.isReady; notify"));
+}
+
+#[test]
+fn shouldAddBehaviorsToNodeLabel_whenDotFormatIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--state-behaviors", "plain", "--format", "dot", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains("    \"Idle\" [label=\"Idle\\nentry /\\nexit /\"];\n"));
+}
+
+#[test]
+fn shouldShowBehaviorsOfStateFromNamespaceOfMachine_whenStatesInOtherNamespacesHaveTheSameName()
+{
+    let cppFileContent = r#"
+namespace first
+{
+struct Idle : public msmf::state<>
+{
+    template <class Event, class Fsm>
+    void on_entry(Event const&, Fsm&) { /* unused */ }
+};
+}
+
+namespace second
+{
+struct Idle : public msmf::state<>
+{
+    template <class Event, class Fsm>
+    void on_exit(Event const&, Fsm&) {}
+};
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Busy, msmf::none, msmf::none>
+    > {};
+};
+}
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--state-behaviors", "plain", "-"]).write_stdin(cppFileContent)
+        .assert().success()
+        .stdout("@startuml\nhide empty description\nIdle : exit /\n[*] --> Idle\nIdle --> Busy : on Start\n@enduml\n");
+}
+
+#[test]
+fn shouldFail_whenUnknownDetailIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--state-behaviors", "all", "-"]).assert().failure()
+        .stderr("Error: \"Unknown state behavior detail: all, expected one of: off, plain, comment, calls.\"\n");
+}
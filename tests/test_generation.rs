@@ -0,0 +1,189 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+namespace events { struct Start {}; struct Stop {}; }
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using Start = events::Start;
+    using initial_state = Idle;
+
+    struct transition_table : boost::mpl::vector<
+        _row<Idle, Start, Busy>,
+        row<Busy, events::Stop, Idle, Log, IsDone>,
+        _row<Orphan, Start, Idle>
+    > {};
+};
+"#;
+
+#[test]
+fn shouldGenerateGoogleTestPerTransition()
+{
+    let expectedOutput = r#"// Transition coverage tests generated by boost-msm-grapher.
+#include <gtest/gtest.h>
+#include <boost/msm/back/state_machine.hpp>
+// TODO: include the header defining the state machines.
+
+namespace
+{
+template <class State, class Machine>
+bool isInState(const Machine& machine)
+{
+    const int stateId = boost::msm::back::get_state_id<typename Machine::stt, State>::value;
+    for (int region = 0; region < Machine::nr_regions::value; ++region) {
+        if (machine.current_state()[region] == stateId) {
+            return true;
+        }
+    }
+    return false;
+}
+}
+
+using MachineDefMachine = boost::msm::back::state_machine<MachineDef>;
+
+namespace
+{
+// Called before processing the event of row 2, to make guard IsDone hold.
+void makeGuardHold_MachineDef_Row2([[maybe_unused]] MachineDefMachine& machine)
+{
+    // TODO: set up the machine so that the guard holds.
+}
+}
+
+TEST(MachineDefTransitions, Row1_Idle_events__Start_Busy)
+{
+    MachineDefMachine machine;
+    machine.start();
+    machine.process_event(events::Start{});
+    EXPECT_TRUE(isInState<Busy>(machine));
+}
+
+TEST(MachineDefTransitions, Row2_Busy_events__Stop_Idle)
+{
+    MachineDefMachine machine;
+    machine.start();
+    machine.process_event(events::Start{});
+    makeGuardHold_MachineDef_Row2(machine);
+    machine.process_event(events::Stop{});
+    // TODO: check effects of action Log.
+    EXPECT_TRUE(isInState<Idle>(machine));
+}
+
+TEST(MachineDefTransitions, Row3_Orphan_events__Start_Idle)
+{
+    GTEST_SKIP() << "State Orphan is unreachable from the initial state.";
+}
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["gen-tests", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldGenerateCatch2TestCases_whenCatch2FrameworkIsSelected()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["gen-tests", "--framework", "catch2", "-"])
+        .write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(predicates::str::contains("#include <catch2/catch_test_macros.hpp>\n"))
+        .stdout(predicates::str::contains(concat!(
+            "TEST_CASE(\"MachineDef: row 1: Idle --events::Start--> Busy\", \"[MachineDef]\")\n",
+            "{\n",
+            "    MachineDefMachine machine;\n",
+            "    machine.start();\n",
+            "    machine.process_event(events::Start{});\n",
+            "    CHECK(isInState<Busy>(machine));\n",
+            "}\n")))
+        .stdout(predicates::str::contains("    SKIP(\"State Orphan is unreachable from the initial state.\");\n"));
+}
+
+#[test]
+fn shouldPlaceTestsInNamespaces_andNumberMachinesOfTheSameName()
+{
+    let cppFileContent = r#"
+namespace app
+{
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    struct transition_table : boost::mpl::vector<_row<Idle, Start, Busy>> {};
+};
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    struct transition_table : boost::mpl::vector<_row<Open, Close, Closed>> {};
+};
+}
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["gen-tests", "-"])
+        .write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(predicates::str::contains(concat!(
+            "namespace app\n",
+            "{\n",
+            "\n",
+            "using app__MachineDefMachine = boost::msm::back::state_machine<app::MachineDef>;\n",
+            "\n",
+            "TEST(app__MachineDefTransitions, Row1_Idle_Start_Busy)\n")))
+        .stdout(predicates::str::contains(concat!(
+            "using app__MachineDef_2Machine = boost::msm::back::state_machine<app::MachineDef>;\n",
+            "\n",
+            "TEST(app__MachineDef_2Transitions, Row1_Open_Close_Closed)\n",
+            "{\n",
+            "    app__MachineDef_2Machine machine;\n")))
+        .stdout(predicates::str::ends_with("}\n} // namespace app\n"));
+}
+
+#[test]
+fn shouldExpectStartState_whenTransitionIsInternal()
+{
+    let cppFileContent = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using initial_state = Busy;
+
+    struct transition_table : boost::mpl::vector<
+        Row<Busy, Tick, boost::msm::front::none, Count, boost::msm::front::none>
+    > {};
+};
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["gen-tests", "-"])
+        .write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(predicates::str::contains(concat!(
+            "TEST(MachineDefTransitions, Row1_Busy_Tick_Busy)\n",
+            "{\n",
+            "    MachineDefMachine machine;\n",
+            "    machine.start();\n",
+            "    machine.process_event(Tick{});\n",
+            "    // TODO: check effects of action Count.\n",
+            "    EXPECT_TRUE(isInState<Busy>(machine));\n",
+            "}\n")));
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["gen-tests", "--framework", "catch2", "-"])
+        .write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(predicates::str::contains("TEST_CASE(\"MachineDef: row 1: Busy --Tick--> Busy\", \"[MachineDef]\")\n"))
+        .stdout(predicates::str::contains("    CHECK(isInState<Busy>(machine));\n"));
+}
+
+#[test]
+fn shouldIncludeAnalyzedFile_whenItIsNotStandardInput()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("machine.hpp");
+    std::fs::write(&path, CPP_FILE_CONTENT).unwrap();
+
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("gen-tests").arg(&path)
+        .assert().success()
+        .stdout(predicates::str::contains("#include <boost/msm/back/state_machine.hpp>\n#include \"machine.hpp\"\n"));
+}
+
+#[test]
+fn shouldFail_whenTestFrameworkIsUnknown()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["gen-tests", "--framework", "doctest", "-"])
+        .assert().failure()
+        .stderr("Error: \"Unknown test framework: doctest, expected one of: gtest, catch2.\"\n");
+}
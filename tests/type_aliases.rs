@@ -0,0 +1,148 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+const CPP_FILE_CONTENT: &str = r#"
+#include <boost/msm/back/state_machine.hpp>
+#include <boost/msm/front/state_machine_def.hpp>
+#include <boost/msm/front/functor_row.hpp>
+
+namespace msmf = boost::msm::front;
+
+namespace states
+{
+struct IdleState : public msmf::state<> {};
+struct BusyState : public msmf::state<> {};
+}
+
+struct Start {};
+struct Finish {};
+struct Log
+{
+    template <class Event, class Fsm, class Source, class Target>
+    void operator()(const Event&, Fsm&, Source&, Target&) {}
+};
+
+using Idle = states::IdleState;
+typedef states::BusyState Busy;
+
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    using initial_state = Idle;
+    using LogAction = Log;
+    typedef msmf::Row<Busy, Finish, states::IdleState, msmf::none, msmf::none> FinishRow;
+
+    struct transition_table : boost::mpl::vector<
+        msmf::Row<Idle, Start, Busy, LogAction, msmf::none>,
+        FinishRow
+    > {};
+};
+"#;
+
+#[test]
+fn shouldShowEachStateOnce_whenStateIsReferencedThroughAlias()
+{
+    let expectedOutput =
+r"@startuml
+hide empty description
+[*] --> Idle
+Idle --> Busy : on Start\ndo LogAction
+Busy --> Idle : on Finish
+@enduml
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-").write_stdin(CPP_FILE_CONTENT).assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldShowCanonicalNames_whenCanonicalNameDisplayIsSelected()
+{
+    let expectedOutput =
+r#"@startuml
+hide empty description
+state "states::IdleState" as states__IdleState
+state "states::BusyState" as states__BusyState
+[*] --> states__IdleState
+states__IdleState --> states__BusyState : on Start\ndo Log
+states__BusyState --> states__IdleState : on Finish
+@enduml
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--names", "canonical", "-"]).write_stdin(CPP_FILE_CONTENT)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldResolveMemberFunctionAliases_whenCanonicalNameDisplayIsSelected()
+{
+    let cppFileContent = r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{
+    using M = MachineDef;
+
+    struct transition_table : boost::mpl::vector<
+        row<StartState, Event, TargetState, &M::action, &M::guard>
+    > {};
+};
+"#;
+    let expectedOutput =
+r"@startuml
+hide empty description
+[*] --> StartState
+StartState --> TargetState : on Event\nif &MachineDef::guard\ndo &MachineDef::action
+@enduml
+";
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--names=canonical", "-"]).write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(expectedOutput);
+}
+
+#[test]
+fn shouldResolveAliasesOfScopeOfMachine_whenOtherScopesDeclareAliasesOfTheSameName()
+{
+    let cppFileContent = r#"
+namespace first
+{
+using Idle = states::FirstIdle;
+
+struct FirstMachineDef : public msmf::state_machine_def<FirstMachineDef>
+{
+    struct transition_table : boost::mpl::vector<msmf::Row<Idle, Start, Busy, msmf::none, msmf::none>> {};
+};
+}
+
+namespace second
+{
+using Idle = states::SecondIdle;
+
+struct SecondMachineDef : public msmf::state_machine_def<SecondMachineDef>
+{
+    using Busy = states::SecondBusy;
+
+    struct transition_table : boost::mpl::vector<msmf::Row<Idle, Start, Busy, msmf::none, msmf::none>> {};
+};
+}
+"#;
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--names", "canonical", "--format", "mermaid", "-"])
+        .write_stdin(cppFileContent)
+        .assert().success()
+        .stdout(concat!(
+            "stateDiagram-v2\n",
+            "    state \"states::FirstIdle\" as states__FirstIdle\n",
+            "    [*] --> states__FirstIdle\n",
+            "    states__FirstIdle --> Busy : on Start\n",
+            "stateDiagram-v2\n",
+            "    state \"states::SecondIdle\" as states__SecondIdle\n",
+            "    state \"states::SecondBusy\" as states__SecondBusy\n",
+            "    [*] --> states__SecondIdle\n",
+            "    states__SecondIdle --> states__SecondBusy : on Start\n"));
+}
+
+#[test]
+fn shouldFail_whenUnknownNameDisplayIsProvided()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--names", "short", "-"]).assert().failure()
+        .stderr("Error: \"Unknown name display: short, expected one of: alias, canonical.\"\n");
+}
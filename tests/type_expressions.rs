@@ -0,0 +1,97 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+
+fn makeCppFileContent(action: &str) -> String
+{
+    format!(r#"
+struct MachineDef : public boost::msm::front::state_machine_def<MachineDef>
+{{
+    using initial_state = State1;
+
+    struct transition_table : boost::mpl::vector<
+        boost::msm::front::Row<State1, Event, State2, {}, boost::msm::front::none>
+    > {{}};
+}};
+"#, action)
+}
+
+fn makeExpectedOutput(actionText: &str) -> String
+{
+    format!("@startuml\nhide empty description\n[*] --> State1\nState1 --> State2 : on Event\\n{}\n@enduml\n", actionText)
+}
+
+#[test]
+fn shouldParseCommaAfterInnerTemplate()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("ActionSequence_<mpl::vector<Log<Start>, Notify>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do Log<Start>; Notify"));
+}
+
+#[test]
+fn shouldParseDeeplyNestedTemplates()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("A<B<C<D, E<F>>, G>, H<I<J>>>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do A<B<C<D, E<F>>, G>, H<I<J>>>"));
+}
+
+#[test]
+fn shouldJoinQualifiedNames_whenTheyContainWhitespace()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("actions :: Log< events ::Start >"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do actions::Log<events::Start>"));
+}
+
+#[test]
+fn shouldParseMemberOfTemplate()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("Select<Log, Notify>::type"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do Select<Log, Notify>::type"));
+}
+
+#[test]
+fn shouldParseNumericTemplateArguments()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("Retry<3, -1>"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do Retry<3, -1>"));
+}
+
+#[test]
+fn shouldParseMemberPointer_whenAmpersandIsSeparated()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("& MachineDef::start"))
+        .assert().success()
+        .stdout(makeExpectedOutput("do &MachineDef::start"));
+}
+
+#[test]
+fn shouldFail_whenQualifiedNameEndsWithScopeOperator()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent("actions:: >"))
+        .assert().failure()
+        .stderr("Error: \"Expected a name after \\\"::\\\", got: TemplateEnd.\"\n");
+}
+
+#[test]
+fn shouldFail_whenTemplatesAreNestedTooDeeply()
+{
+    let action = format!("{}A{}", "A<".repeat(1000), ">".repeat(1000));
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().arg("-")
+        .write_stdin(makeCppFileContent(&action))
+        .assert().failure()
+        .stderr("Error: \"While parsing an action, templates were nested too deeply.\"\n");
+}
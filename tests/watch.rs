@@ -0,0 +1,83 @@
+#![allow(non_snake_case)]
+
+mod common;
+use common::APP_NAME;
+
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+
+const CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+const CHANGED_CPP_FILE_CONTENT: &str = r#"
+struct MachineDef : public msmf::state_machine_def<MachineDef>
+{
+    struct transition_table : mpl::vector<
+        msmf::Row<Idle, Start, Running, msmf::none, msmf::none>,
+        msmf::Row<Running, Stop, Idle, msmf::none, msmf::none>
+    > {};
+};
+"#;
+
+fn waitForFileContaining(path: &Path, text: &str) -> bool
+{
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if std::fs::read_to_string(path).is_ok_and(|content| content.contains(text)) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    false
+}
+
+#[test]
+fn shouldRegenerateDiagram_whenInputFileChanges()
+{
+    let directory = tempfile::tempdir().unwrap();
+    let inputPath = directory.path().join("machine.cpp");
+    let outputPath = directory.path().join("machine.puml");
+    std::fs::write(&inputPath, CPP_FILE_CONTENT).unwrap();
+    let mut process = std::process::Command::new(assert_cmd::cargo::cargo_bin(APP_NAME))
+        .args(["--watch", "--no-config", "-o"]).arg(&outputPath).arg(&inputPath)
+        .stderr(Stdio::piped())
+        .spawn().unwrap();
+
+    let isFirstDiagramWritten = waitForFileContaining(&outputPath, "Idle --> Running : on Start");
+    std::fs::write(&inputPath, CHANGED_CPP_FILE_CONTENT).unwrap();
+    let isDiagramRegenerated = waitForFileContaining(&outputPath, "Running --> Idle : on Stop");
+    process.kill().unwrap();
+    process.wait().unwrap();
+    let mut errorOutput = String::new();
+    process.stderr.take().unwrap().read_to_string(&mut errorOutput).unwrap();
+
+    assert!(isFirstDiagramWritten);
+    assert!(isDiagramRegenerated);
+    assert!(errorOutput.contains("state Running in MachineDef has no outgoing transitions"), "{}", errorOutput);
+    assert!(errorOutput.contains("Summary: 0 error(s) and 1 warning(s) in 1 machine(s).\n"), "{}", errorOutput);
+}
+
+#[test]
+fn shouldFail_whenWatchHasNoOutput()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--watch", "machine.cpp"])
+        .assert().failure()
+        .stderr("Error: \"Option --watch requires --output or --output-dir.\"\n");
+}
+
+#[test]
+fn shouldFail_whenWatchingStandardInput()
+{
+    assert_cmd::Command::cargo_bin(APP_NAME).unwrap().args(["--watch", "-o", "machine.puml", "-"])
+        .assert().failure()
+        .stderr("Error: \"Option --watch cannot watch standard input.\"\n");
+}